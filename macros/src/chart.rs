@@ -0,0 +1,538 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{braced, Error, Ident, LitInt, Pat, Path, Result, Token, Type, Visibility};
+
+mod keyword {
+    syn::custom_keyword!(context);
+    syn::custom_keyword!(event);
+    syn::custom_keyword!(depth);
+    syn::custom_keyword!(machine);
+    syn::custom_keyword!(entry);
+    syn::custom_keyword!(exit);
+    syn::custom_keyword!(initial);
+    syn::custom_keyword!(history);
+    syn::custom_keyword!(id);
+    syn::custom_keyword!(on);
+    syn::custom_keyword!(external);
+    syn::custom_keyword!(shallow);
+    syn::custom_keyword!(deep);
+}
+
+pub struct Chart {
+    context: Type,
+    event: Type,
+    depth: Option<LitInt>,
+    machine: Option<Type>,
+    states: Vec<Node>,
+}
+
+struct Node {
+    vis: Visibility,
+    name: Ident,
+    entry: Option<Path>,
+    exit: Option<Path>,
+    initial: Option<(Ident, Option<Path>)>,
+    history: Option<Ident>,
+    id: Option<LitInt>,
+    rules: Vec<Rule>,
+    children: Vec<Node>,
+}
+
+enum Kind {
+    Local,
+    External,
+    History,
+}
+
+struct Rule {
+    pattern: Pat,
+    target: Option<(Kind, Ident)>,
+    action: Option<Path>,
+}
+
+impl Parse for Chart {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<keyword::context>()?;
+        input.parse::<Token![=]>()?;
+        let context = input.parse()?;
+        input.parse::<Token![;]>()?;
+
+        input.parse::<keyword::event>()?;
+        input.parse::<Token![=]>()?;
+        let event = input.parse()?;
+        input.parse::<Token![;]>()?;
+
+        let depth = if input.peek(keyword::depth) && input.peek2(Token![=]) {
+            input.parse::<keyword::depth>()?;
+            input.parse::<Token![=]>()?;
+            let depth = input.parse()?;
+            input.parse::<Token![;]>()?;
+            Some(depth)
+        } else {
+            None
+        };
+
+        let machine = if input.peek(keyword::machine) && input.peek2(Token![=]) {
+            input.parse::<keyword::machine>()?;
+            input.parse::<Token![=]>()?;
+            let machine = input.parse()?;
+            input.parse::<Token![;]>()?;
+            Some(machine)
+        } else {
+            None
+        };
+
+        let mut states = Vec::new();
+
+        while !input.is_empty() {
+            states.push(input.parse()?);
+        }
+
+        Ok(Self {
+            context,
+            event,
+            depth,
+            machine,
+            states,
+        })
+    }
+}
+
+impl Parse for Node {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let vis = input.parse()?;
+        let name = input.parse()?;
+
+        let content;
+        braced!(content in input);
+
+        let mut node = Self {
+            vis,
+            name,
+            entry: None,
+            exit: None,
+            initial: None,
+            history: None,
+            id: None,
+            rules: Vec::new(),
+            children: Vec::new(),
+        };
+
+        while !content.is_empty() {
+            if content.peek(keyword::entry) && content.peek2(Token![=]) {
+                let keyword = content.parse::<keyword::entry>()?;
+                content.parse::<Token![=]>()?;
+                let path = content.parse()?;
+                content.parse::<Token![;]>()?;
+
+                if node.entry.replace(path).is_some() {
+                    return Err(Error::new(keyword.span, "duplicate `entry` behavior"));
+                }
+            } else if content.peek(keyword::exit) && content.peek2(Token![=]) {
+                let keyword = content.parse::<keyword::exit>()?;
+                content.parse::<Token![=]>()?;
+                let path = content.parse()?;
+                content.parse::<Token![;]>()?;
+
+                if node.exit.replace(path).is_some() {
+                    return Err(Error::new(keyword.span, "duplicate `exit` behavior"));
+                }
+            } else if content.peek(keyword::initial) && content.peek2(Token![=]) {
+                let keyword = content.parse::<keyword::initial>()?;
+                content.parse::<Token![=]>()?;
+                let target = content.parse()?;
+                let action = if content.peek(Token![/]) {
+                    content.parse::<Token![/]>()?;
+                    Some(content.parse()?)
+                } else {
+                    None
+                };
+                content.parse::<Token![;]>()?;
+
+                if node.initial.replace((target, action)).is_some() {
+                    return Err(Error::new(keyword.span, "duplicate `initial` state"));
+                }
+            } else if content.peek(keyword::id) && content.peek2(Token![=]) {
+                let keyword = content.parse::<keyword::id>()?;
+                content.parse::<Token![=]>()?;
+                let id = content.parse::<LitInt>()?;
+                id.base10_parse::<u32>()?;
+                content.parse::<Token![;]>()?;
+
+                if node.id.replace(id).is_some() {
+                    return Err(Error::new(keyword.span, "duplicate state `id`"));
+                }
+            } else if content.peek(keyword::history) && content.peek2(Token![=]) {
+                let keyword = content.parse::<keyword::history>()?;
+                content.parse::<Token![=]>()?;
+                let kind = if content.peek(keyword::shallow) || content.peek(keyword::deep) {
+                    content.parse()?
+                } else {
+                    return Err(content.error("expected `shallow` or `deep`"));
+                };
+                content.parse::<Token![;]>()?;
+
+                if node.history.replace(kind).is_some() {
+                    return Err(Error::new(keyword.span, "duplicate `history` kind"));
+                }
+            } else if content.peek(keyword::on) && !content.peek2(syn::token::Brace) {
+                content.parse::<keyword::on>()?;
+                node.rules.push(content.parse()?);
+            } else {
+                node.children.push(content.parse()?);
+            }
+        }
+
+        Ok(node)
+    }
+}
+
+impl Parse for Rule {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let pattern = Pat::parse_multi_with_leading_vert(input)?;
+
+        let target = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            let kind = if input.peek(keyword::external) && input.peek2(Ident) {
+                input.parse::<keyword::external>()?;
+                Kind::External
+            } else if input.peek(keyword::history) && input.peek2(Ident) {
+                input.parse::<keyword::history>()?;
+                Kind::History
+            } else {
+                Kind::Local
+            };
+            Some((kind, input.parse()?))
+        } else {
+            None
+        };
+
+        let action = if input.peek(Token![/]) {
+            input.parse::<Token![/]>()?;
+            Some(input.parse()?)
+        } else if target.is_none() {
+            return Err(input.error("expected `=>` or `/`"));
+        } else {
+            None
+        };
+
+        input.parse::<Token![;]>()?;
+
+        Ok(Self {
+            pattern,
+            target,
+            action,
+        })
+    }
+}
+
+fn struct_name(name: &Ident) -> Result<Ident> {
+    let name_string = name.to_string();
+    let screaming = name_string
+        .chars()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+
+    let camel: String = name_string
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            core::iter::once(first)
+                .chain(chars.map(|c| c.to_ascii_lowercase()))
+                .collect::<String>()
+        })
+        .collect();
+
+    if !screaming || camel.is_empty() || *name == camel {
+        return Err(Error::new(
+            name.span(),
+            "state names must be in SCREAMING_SNAKE_CASE",
+        ));
+    }
+
+    Ok(format_ident!("{}", camel, span = name.span()))
+}
+
+fn flatten<'a>(
+    nodes: &'a [Node],
+    parent: Option<&'a Ident>,
+    depth: usize,
+    states: &mut Vec<(&'a Node, Option<&'a Ident>, usize)>,
+) {
+    for node in nodes {
+        states.push((node, parent, depth));
+        flatten(&node.children, Some(&node.name), depth + 1, states);
+    }
+}
+
+pub fn expand(chart: Chart) -> Result<TokenStream> {
+    let mut states = Vec::new();
+    flatten(&chart.states, None, 1, &mut states);
+
+    let mut errors: Option<Error> = None;
+    let mut report = |error: Error| match errors.as_mut() {
+        Some(errors) => errors.combine(error),
+        None => errors = Some(error),
+    };
+
+    for node in chart.states.iter().skip(1) {
+        report(Error::new(
+            node.name.span(),
+            format!(
+                "state `{}` is an additional root state, nest it under `{}`",
+                node.name, chart.states[0].name
+            ),
+        ));
+    }
+
+    if let Some(limit) = &chart.depth {
+        let limit = limit.base10_parse::<usize>()?;
+
+        for (node, _, depth) in states.iter() {
+            if *depth > limit {
+                report(Error::new(
+                    node.name.span(),
+                    format!(
+                        "state `{}` exceeds the tree depth limit of {}",
+                        node.name, limit
+                    ),
+                ));
+            }
+        }
+    }
+
+    let mut parents = HashMap::new();
+
+    for (node, parent, _) in states.iter() {
+        match parents.entry(node.name.to_string()) {
+            Entry::Occupied(_) => report(Error::new(
+                node.name.span(),
+                format!("duplicate state `{}`", node.name),
+            )),
+            Entry::Vacant(entry) => {
+                entry.insert(*parent);
+            }
+        }
+    }
+
+    let mut ids = HashMap::new();
+
+    for (node, _, _) in states.iter() {
+        if let Some(id) = &node.id {
+            let value = id.base10_parse::<u32>()?;
+
+            if let Some(other) = ids.insert(value, &node.name) {
+                report(Error::new(
+                    id.span(),
+                    format!(
+                        "state `{}` reuses id {} of state `{}`",
+                        node.name, value, other
+                    ),
+                ));
+            }
+        }
+    }
+
+    for (node, _, _) in states.iter() {
+        let targets = node
+            .rules
+            .iter()
+            .filter_map(|rule| rule.target.as_ref().map(|(_, target)| target))
+            .chain(node.initial.as_ref().map(|(target, _)| target));
+
+        for target in targets {
+            if !parents.contains_key(&target.to_string()) {
+                report(Error::new(
+                    target.span(),
+                    format!("unknown state `{}`", target),
+                ));
+            }
+        }
+
+        if let Some((target, _)) = &node.initial {
+            if let Some(Some(parent)) = parents.get(&target.to_string()) {
+                if *parent != &node.name {
+                    report(Error::new(
+                        target.span(),
+                        format!(
+                            "initial state `{}` is not a child of `{}`",
+                            target, node.name
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    let context = &chart.context;
+    let event = &chart.event;
+
+    let mut output = TokenStream::new();
+
+    for (node, parent, _) in states.iter() {
+        let struct_name = match struct_name(&node.name) {
+            Ok(struct_name) => struct_name,
+            Err(error) => {
+                report(error);
+                continue;
+            }
+        };
+
+        let vis = &node.vis;
+        let name = &node.name;
+
+        let parent = parent.map(|parent| {
+            quote! {
+                fn parent(&self) -> Option<&'static dyn ::hsm::State<#context, #event>> {
+                    Some(&#parent)
+                }
+            }
+        });
+
+        let entry = node.entry.as_ref().map(|entry| {
+            quote! {
+                fn entry(&self, context: &mut #context) {
+                    #entry(context)
+                }
+            }
+        });
+
+        let initial = node.initial.as_ref().map(|(target, action)| {
+            let action = match action {
+                Some(action) => quote!(Some(#action)),
+                None => quote!(None),
+            };
+
+            quote! {
+                fn initial(&self) -> ::hsm::Initial<#context, #event> {
+                    Some((&#target, #action))
+                }
+            }
+        });
+
+        let label = name.to_string();
+
+        let id = node.id.as_ref().map(|id| {
+            quote! {
+                fn id(&self) -> Option<u32> {
+                    Some(#id)
+                }
+            }
+        });
+
+        let history = node.history.as_ref().map(|kind| {
+            let kind = if kind == "deep" {
+                quote!(::hsm::HistoryKind::Deep)
+            } else {
+                quote!(::hsm::HistoryKind::Shallow)
+            };
+
+            quote! {
+                fn history(&self) -> Option<::hsm::HistoryKind> {
+                    Some(#kind)
+                }
+            }
+        });
+
+        let transition = if node.rules.is_empty() {
+            None
+        } else {
+            let arms = node.rules.iter().map(|rule| {
+                let pattern = &rule.pattern;
+                let action = match &rule.action {
+                    Some(action) => quote!(Some(#action)),
+                    None => quote!(None),
+                };
+
+                match &rule.target {
+                    None => quote! {
+                        #pattern => ::hsm::Transition::Internal(#action),
+                    },
+                    Some((Kind::Local, target)) => quote! {
+                        #pattern => ::hsm::Transition::Local(&#target, #action),
+                    },
+                    Some((Kind::External, target)) => quote! {
+                        #pattern => ::hsm::Transition::External(&#target, #action),
+                    },
+                    Some((Kind::History, target)) => quote! {
+                        #pattern => ::hsm::Transition::History(&#target, #action),
+                    },
+                }
+            });
+
+            Some(quote! {
+                fn transition(
+                    &self,
+                    _context: &mut #context,
+                    event: &#event,
+                ) -> ::hsm::Transition<#context, #event> {
+                    #[allow(unreachable_patterns)]
+                    match event {
+                        #(#arms)*
+                        _ => ::hsm::Transition::Unknown,
+                    }
+                }
+            })
+        };
+
+        let exit = node.exit.as_ref().map(|exit| {
+            quote! {
+                fn exit(&self, context: &mut #context) {
+                    #exit(context)
+                }
+            }
+        });
+
+        output.extend(quote! {
+            #vis struct #struct_name;
+
+            impl ::hsm::State<#context, #event> for #struct_name {
+                fn name(&self) -> &'static str {
+                    #label
+                }
+
+                #id
+                #parent
+                #entry
+                #initial
+                #history
+                #transition
+                #exit
+            }
+
+            #vis static #name: #struct_name = #struct_name;
+        });
+    }
+
+    let depth = states.iter().map(|(_, _, depth)| *depth).max();
+
+    if let (Some(root), Some(depth)) = (chart.states.first(), depth) {
+        if let Ok(root_name) = struct_name(&root.name) {
+            output.extend(quote! {
+                impl #root_name {
+                    pub const DEPTH: usize = #depth;
+                }
+            });
+
+            if let Some(machine) = &chart.machine {
+                output.extend(quote_spanned! {machine.span()=>
+                    const _: () = assert!(
+                        <#machine>::DEPTH >= #root_name::DEPTH,
+                        "State tree exceeds the machine depth limit!"
+                    );
+                });
+            }
+        }
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(output),
+    }
+}
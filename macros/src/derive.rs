@@ -0,0 +1,354 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Attribute, DeriveInput, Error, FnArg, ImplItem, ItemImpl, LitInt, LitStr, Pat,
+    Path, Result, Type,
+};
+
+pub fn expand_state(input: DeriveInput) -> Result<TokenStream> {
+    let mut name: Option<LitStr> = None;
+    let mut id: Option<LitInt> = None;
+    let mut parent: Option<Path> = None;
+    let mut initial: Option<Path> = None;
+    let mut context: Option<Type> = None;
+    let mut event: Option<Type> = None;
+    let mut behavior = false;
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("state"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("id") {
+                let value: LitInt = meta.value()?.parse()?;
+                value.base10_parse::<u32>()?;
+
+                if id.replace(value).is_some() {
+                    return Err(meta.error("duplicate state `id`"));
+                }
+            } else if meta.path.is_ident("parent") {
+                parent = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("initial") {
+                initial = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("context") {
+                context = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("event") {
+                event = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("behavior") {
+                if behavior {
+                    return Err(meta.error("duplicate `behavior` flag"));
+                }
+
+                behavior = true;
+            } else {
+                return Err(meta.error("unsupported state property"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    let context = context.ok_or_else(|| {
+        Error::new(
+            input.ident.span(),
+            "missing `#[state(context = ...)]` attribute",
+        )
+    })?;
+    let event = event.ok_or_else(|| {
+        Error::new(
+            input.ident.span(),
+            "missing `#[state(event = ...)]` attribute",
+        )
+    })?;
+
+    let label = name.unwrap_or_else(|| LitStr::new(&input.ident.to_string(), input.ident.span()));
+
+    let id = id.map(|id| {
+        quote! {
+            fn id(&self) -> Option<u32> {
+                Some(#id)
+            }
+        }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let parent = parent.map(|parent| {
+        quote! {
+            fn parent(&self) -> Option<&'static dyn ::hsm::State<#context, #event>> {
+                Some(&#parent)
+            }
+        }
+    });
+
+    let initial = initial.map(|initial| {
+        quote! {
+            fn initial(&self) -> ::hsm::Initial<#context, #event> {
+                Some((&#initial, None))
+            }
+        }
+    });
+
+    if !behavior {
+        return Ok(quote! {
+            impl #impl_generics ::hsm::State<#context, #event> for #name #type_generics #where_clause {
+                fn name(&self) -> &'static str {
+                    #label
+                }
+
+                #id
+                #parent
+                #initial
+            }
+        });
+    }
+
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(Self: ::hsm::StateBehavior<#context, #event>));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::hsm::State<#context, #event> for #name #type_generics #where_clause {
+            fn name(&self) -> &'static str {
+                #label
+            }
+
+            #id
+            #parent
+            #initial
+
+            fn entry(&self, context: &mut #context) {
+                ::hsm::StateBehavior::<#context, #event>::entry(self, context)
+            }
+
+            fn transition(
+                &self,
+                context: &mut #context,
+                event: &#event,
+            ) -> ::hsm::Transition<#context, #event> {
+                ::hsm::StateBehavior::<#context, #event>::transition(self, context, event)
+            }
+
+            fn exit(&self, context: &mut #context) {
+                ::hsm::StateBehavior::<#context, #event>::exit(self, context)
+            }
+        }
+
+        impl #impl_generics ::hsm::__private::DerivedBehavior for #name #type_generics #where_clause {}
+    })
+}
+
+enum Tag {
+    Entry,
+    Exit,
+    On(Pat),
+}
+
+fn take_tag(attrs: &mut Vec<Attribute>) -> Result<Option<Tag>> {
+    let mut tag = None;
+    let mut result = Ok(());
+
+    attrs.retain(|attr| {
+        let parsed = if attr.path().is_ident("entry") {
+            attr.meta.require_path_only().map(|_| Tag::Entry)
+        } else if attr.path().is_ident("exit") {
+            attr.meta.require_path_only().map(|_| Tag::Exit)
+        } else if attr.path().is_ident("on") {
+            attr.parse_args_with(Pat::parse_multi_with_leading_vert)
+                .map(Tag::On)
+        } else {
+            return true;
+        };
+
+        match parsed {
+            Ok(parsed) if tag.is_none() => tag = Some(parsed),
+            Ok(_) => {
+                result = Err(Error::new(
+                    attr.span(),
+                    "method is already tagged with a behavior",
+                ))
+            }
+            Err(error) => result = Err(error),
+        }
+
+        false
+    });
+
+    result.map(|()| tag)
+}
+
+fn argument_type(input: &FnArg) -> Result<(&Type, bool)> {
+    match input {
+        FnArg::Typed(argument) => match &*argument.ty {
+            Type::Reference(reference) => Ok((&reference.elem, reference.mutability.is_some())),
+            _ => Err(Error::new(
+                argument.ty.span(),
+                "expected a reference argument",
+            )),
+        },
+        FnArg::Receiver(receiver) => Err(Error::new(receiver.span(), "unexpected receiver")),
+    }
+}
+
+pub fn expand_behavior(mut input: ItemImpl) -> Result<TokenStream> {
+    let mut context: Option<Type> = None;
+    let mut event: Option<Type> = None;
+
+    let mut entry = None;
+    let mut exit = None;
+    let mut arms = Vec::new();
+
+    for item in input.items.iter_mut() {
+        let method = match item {
+            ImplItem::Fn(method) => method,
+            _ => continue,
+        };
+
+        let tag = match take_tag(&mut method.attrs)? {
+            Some(tag) => tag,
+            None => continue,
+        };
+
+        let ident = &method.sig.ident;
+        let receiver = method.sig.receiver().is_some();
+        let arguments: Vec<_> = method
+            .sig
+            .inputs
+            .iter()
+            .filter(|input| matches!(input, FnArg::Typed(_)))
+            .collect();
+
+        let call = |arguments: TokenStream| {
+            if receiver {
+                quote!(self.#ident(#arguments))
+            } else {
+                quote!(Self::#ident(#arguments))
+            }
+        };
+
+        match tag {
+            Tag::Entry | Tag::Exit => {
+                if arguments.len() != 1 {
+                    return Err(Error::new(
+                        method.sig.span(),
+                        "entry and exit behaviors take a single context argument",
+                    ));
+                }
+
+                let (ty, mutable) = argument_type(arguments[0])?;
+                if !mutable {
+                    return Err(Error::new(ty.span(), "context must be passed by `&mut`"));
+                }
+                context.get_or_insert_with(|| ty.clone());
+
+                let behavior = call(quote!(context));
+
+                if let Tag::Entry = tag {
+                    if entry.replace(behavior).is_some() {
+                        return Err(Error::new(ident.span(), "duplicate entry behavior"));
+                    }
+                } else if exit.replace(behavior).is_some() {
+                    return Err(Error::new(ident.span(), "duplicate exit behavior"));
+                }
+            }
+            Tag::On(pattern) => {
+                if arguments.len() != 2 {
+                    return Err(Error::new(
+                        method.sig.span(),
+                        "transition behaviors take context and event arguments",
+                    ));
+                }
+
+                let (ty, mutable) = argument_type(arguments[0])?;
+                if !mutable {
+                    return Err(Error::new(ty.span(), "context must be passed by `&mut`"));
+                }
+                context.get_or_insert_with(|| ty.clone());
+
+                let (ty, _) = argument_type(arguments[1])?;
+                event.get_or_insert_with(|| ty.clone());
+
+                let behavior = call(quote!(context, event));
+
+                arms.push(quote! {
+                    #pattern => #behavior,
+                });
+            }
+        }
+    }
+
+    let context_param = format_ident!("__C");
+    let event_param = format_ident!("__E");
+
+    let mut generics = Vec::new();
+    let context = context.map(|context| quote!(#context)).unwrap_or_else(|| {
+        generics.push(quote!(#context_param: 'static));
+        quote!(#context_param)
+    });
+    let event = event.map(|event| quote!(#event)).unwrap_or_else(|| {
+        generics.push(quote!(#event_param: 'static));
+        quote!(#event_param)
+    });
+
+    let self_ty = &input.self_ty;
+
+    let entry = entry.map(|entry| {
+        quote! {
+            fn entry(&self, context: &mut #context) {
+                #entry
+            }
+        }
+    });
+
+    let transition = if arms.is_empty() {
+        None
+    } else {
+        Some(quote! {
+            fn transition(
+                &self,
+                context: &mut #context,
+                event: &#event,
+            ) -> ::hsm::Transition<#context, #event> {
+                #[allow(unreachable_patterns)]
+                match event {
+                    #(#arms)*
+                    _ => ::hsm::Transition::Unknown,
+                }
+            }
+        })
+    };
+
+    let exit = exit.map(|exit| {
+        quote! {
+            fn exit(&self, context: &mut #context) {
+                #exit
+            }
+        }
+    });
+
+    Ok(quote! {
+        #input
+
+        impl<#(#generics),*> ::hsm::StateBehavior<#context, #event> for #self_ty {
+            #entry
+            #transition
+            #exit
+        }
+
+        const _: () = {
+            fn derived<T: ?Sized + ::hsm::__private::DerivedBehavior>() {}
+
+            fn check() {
+                derived::<#self_ty>();
+            }
+        };
+    })
+}
@@ -0,0 +1,28 @@
+extern crate proc_macro;
+
+mod chart;
+mod derive;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemImpl};
+
+#[proc_macro]
+pub fn hsm(input: TokenStream) -> TokenStream {
+    chart::expand(parse_macro_input!(input as chart::Chart))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(State, attributes(state))]
+pub fn derive_state(input: TokenStream) -> TokenStream {
+    derive::expand_state(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_attribute]
+pub fn behavior(_args: TokenStream, input: TokenStream) -> TokenStream {
+    derive::expand_behavior(parse_macro_input!(input as ItemImpl))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
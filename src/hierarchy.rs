@@ -0,0 +1,232 @@
+use crate::{Behavior, DispatchError, DispatchOutcome};
+
+pub(crate) enum Step<I, C, E> {
+    Unknown,
+    Internal(Behavior<C, E>),
+    Local(I, Behavior<C, E>),
+    External(I, Behavior<C, E>),
+}
+
+pub(crate) trait Hierarchy<C, E, const DEPTH: usize> {
+    type Id: Copy + PartialEq;
+
+    fn parent(&self, state: Self::Id) -> Result<Option<Self::Id>, DispatchError>;
+
+    fn initial(&self, state: Self::Id) -> Result<Option<Self::Id>, DispatchError>;
+
+    fn transition(
+        &self,
+        state: Self::Id,
+        context: &mut C,
+        event: &E,
+    ) -> Result<Step<Self::Id, C, E>, DispatchError>;
+
+    fn entry(&self, state: Self::Id, context: &mut C) -> Result<(), DispatchError>;
+
+    fn exit(&self, state: Self::Id, context: &mut C) -> Result<(), DispatchError>;
+
+    fn enter_initial(
+        &self,
+        context: &mut C,
+        initial_state: Self::Id,
+    ) -> Result<Self::Id, DispatchError> {
+        let mut states = [initial_state; DEPTH];
+        let depth = self.ancestors(&mut states)?;
+
+        self.settles(initial_state, depth)?;
+
+        for state in states[..depth].iter().rev() {
+            self.entry(*state, context)?;
+        }
+
+        self.descend(context, initial_state)
+    }
+
+    fn exit_active(&self, context: &mut C, active_state: Self::Id) -> Result<(), DispatchError> {
+        let mut states = [active_state; DEPTH];
+        let depth = self.ancestors(&mut states)?;
+
+        for state in states[..depth].iter() {
+            self.exit(*state, context)?;
+        }
+
+        Ok(())
+    }
+
+    fn react(
+        &self,
+        context: &mut C,
+        event: &E,
+        active_state: Self::Id,
+    ) -> Result<(DispatchOutcome, Self::Id), DispatchError> {
+        let mut effective_state = active_state;
+
+        loop {
+            match self.transition(effective_state, context, event)? {
+                Step::Unknown => {}
+                Step::Internal(behavior) => {
+                    Self::perform(context, event, behavior);
+
+                    return Ok((DispatchOutcome::Handled, active_state));
+                }
+                Step::Local(target_state, behavior) => {
+                    return self.transit(
+                        context,
+                        active_state,
+                        effective_state,
+                        target_state,
+                        false,
+                        |context| Self::perform(context, event, behavior),
+                    );
+                }
+                Step::External(target_state, behavior) => {
+                    return self.transit(
+                        context,
+                        active_state,
+                        effective_state,
+                        target_state,
+                        true,
+                        |context| Self::perform(context, event, behavior),
+                    );
+                }
+            }
+
+            match self.parent(effective_state)? {
+                Some(parent_state) => effective_state = parent_state,
+                None => return Err(DispatchError::UnhandledEvent),
+            }
+        }
+    }
+
+    fn perform(context: &mut C, event: &E, behavior: Behavior<C, E>) {
+        if let Some(action) = behavior {
+            action(context, event);
+        }
+    }
+
+    fn transit(
+        &self,
+        context: &mut C,
+        active_state: Self::Id,
+        source_state: Self::Id,
+        target_state: Self::Id,
+        external: bool,
+        effect: impl FnOnce(&mut C),
+    ) -> Result<(DispatchOutcome, Self::Id), DispatchError> {
+        let mut sources = [source_state; DEPTH];
+        let mut targets = [target_state; DEPTH];
+
+        let source_depth = self.ancestors(&mut sources)?;
+        let target_depth = self.ancestors(&mut targets)?;
+
+        if source_state == target_state && !external && self.initial(target_state)?.is_none() {
+            effect(context);
+
+            return Ok((DispatchOutcome::Handled, active_state));
+        }
+
+        let mut common_ancestor = None;
+
+        'outer: for source in sources[..source_depth].iter() {
+            for (j, target) in targets[..target_depth].iter().enumerate() {
+                if source == target {
+                    common_ancestor = Some((*source, j));
+                    break 'outer;
+                }
+            }
+        }
+
+        let (common_ancestor, target_top) = common_ancestor.ok_or(DispatchError::DisjointStates)?;
+
+        self.settles(target_state, target_depth)?;
+
+        let external =
+            external && (common_ancestor == source_state || common_ancestor == target_state);
+
+        let mut actives = [active_state; DEPTH];
+        let active_depth = self.ancestors(&mut actives)?;
+
+        for state in actives[..active_depth].iter() {
+            if *state == common_ancestor && !external {
+                break;
+            }
+
+            self.exit(*state, context)?;
+
+            if *state == common_ancestor {
+                break;
+            }
+        }
+
+        effect(context);
+
+        let path = if external {
+            &targets[..=target_top]
+        } else {
+            &targets[..target_top]
+        };
+
+        for state in path.iter().rev() {
+            self.entry(*state, context)?;
+        }
+
+        let leaf_state = self.descend(context, target_state)?;
+
+        Ok((DispatchOutcome::Transitioned, leaf_state))
+    }
+
+    fn ancestors(&self, states: &mut [Self::Id; DEPTH]) -> Result<usize, DispatchError> {
+        let mut depth = 1;
+
+        let mut topmost_state = states[0];
+
+        while let Some(parent_state) = self.parent(topmost_state)? {
+            if depth == DEPTH {
+                return Err(DispatchError::DepthExceeded);
+            }
+
+            topmost_state = parent_state;
+
+            states[depth] = topmost_state;
+            depth += 1;
+        }
+
+        Ok(depth)
+    }
+
+    fn settles(&self, composite_state: Self::Id, depth: usize) -> Result<(), DispatchError> {
+        let mut target_state = composite_state;
+        let mut depth = depth;
+
+        while let Some(child_state) = self.initial(target_state)? {
+            if self.parent(child_state)? != Some(target_state) {
+                return Err(DispatchError::InvalidInitialState);
+            }
+
+            if depth == DEPTH {
+                return Err(DispatchError::DepthExceeded);
+            }
+
+            target_state = child_state;
+            depth += 1;
+        }
+
+        Ok(())
+    }
+
+    fn descend(
+        &self,
+        context: &mut C,
+        composite_state: Self::Id,
+    ) -> Result<Self::Id, DispatchError> {
+        let mut target_state = composite_state;
+
+        while let Some(child_state) = self.initial(target_state)? {
+            self.entry(child_state, context)?;
+
+            target_state = child_state;
+        }
+
+        Ok(target_state)
+    }
+}
@@ -0,0 +1,166 @@
+use core::marker::PhantomData;
+
+use crate::hierarchy::{Hierarchy, Step};
+use crate::{Behavior, DispatchError, DispatchOutcome};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StateId(pub usize);
+
+pub enum IndexedTransition<C, E> {
+    Unknown,
+    Internal(Behavior<C, E>),
+    Local(StateId, Behavior<C, E>),
+    External(StateId, Behavior<C, E>),
+}
+
+pub trait IndexedState<C, E> {
+    fn parent(&self) -> Option<StateId> {
+        None
+    }
+
+    fn entry(&self, _context: &mut C) {}
+
+    fn initial(&self) -> Option<StateId> {
+        None
+    }
+
+    fn transition(&self, _context: &mut C, _event: &E) -> IndexedTransition<C, E> {
+        IndexedTransition::<C, E>::Unknown
+    }
+
+    fn exit(&self, _context: &mut C) {}
+}
+
+pub struct IndexedStateMachine<C, E, S, const N: usize, const DEPTH: usize = 8> {
+    states: [S; N],
+    initial_state: StateId,
+    active_state: Option<StateId>,
+    marker: PhantomData<fn(&mut C, &E)>,
+}
+
+impl<C, E, S: IndexedState<C, E>, const N: usize, const DEPTH: usize>
+    IndexedStateMachine<C, E, S, N, DEPTH>
+{
+    const DEPTH_CHECK: () = assert!(DEPTH > 0, "State tree depth limit must be positive!");
+
+    pub fn new(states: [S; N], initial_state: StateId) -> Self {
+        let () = Self::DEPTH_CHECK;
+
+        Self {
+            states,
+            initial_state,
+            active_state: None,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn state(&self, id: StateId) -> Option<&S> {
+        self.states.get(id.0)
+    }
+
+    pub fn active(&self) -> StateId {
+        self.active_state.unwrap_or(self.initial_state)
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.active_state.is_some()
+    }
+
+    pub fn start(&mut self, context: &mut C) {
+        if let Err(error) = self.try_start(context) {
+            panic!("{}", error);
+        }
+    }
+
+    pub fn try_start(&mut self, context: &mut C) -> Result<(), DispatchError> {
+        if self.active_state.is_some() {
+            return Err(DispatchError::AlreadyStarted);
+        }
+
+        self.active_state = Some(self.enter_initial(context, self.initial_state)?);
+
+        Ok(())
+    }
+
+    pub fn stop(&mut self, context: &mut C) {
+        if let Err(error) = self.try_stop(context) {
+            panic!("{}", error);
+        }
+    }
+
+    pub fn try_stop(&mut self, context: &mut C) -> Result<(), DispatchError> {
+        let active_state = self.active_state.ok_or(DispatchError::NotStarted)?;
+
+        self.exit_active(context, active_state)?;
+        self.active_state = None;
+
+        Ok(())
+    }
+
+    pub fn dispatch(&mut self, context: &mut C, event: &E) {
+        if let Err(error) = self.try_dispatch(context, event) {
+            panic!("{}", error);
+        }
+    }
+
+    pub fn try_dispatch(
+        &mut self,
+        context: &mut C,
+        event: &E,
+    ) -> Result<DispatchOutcome, DispatchError> {
+        let active_state = self.active_state.ok_or(DispatchError::NotStarted)?;
+
+        let (outcome, leaf_state) = self.react(context, event, active_state)?;
+        self.active_state = Some(leaf_state);
+
+        Ok(outcome)
+    }
+
+    fn get(&self, id: StateId) -> Result<&S, DispatchError> {
+        self.states.get(id.0).ok_or(DispatchError::InvalidState)
+    }
+}
+
+impl<C, E, S: IndexedState<C, E>, const N: usize, const DEPTH: usize> Hierarchy<C, E, DEPTH>
+    for IndexedStateMachine<C, E, S, N, DEPTH>
+{
+    type Id = StateId;
+
+    fn parent(&self, state: StateId) -> Result<Option<StateId>, DispatchError> {
+        Ok(self.get(state)?.parent())
+    }
+
+    fn initial(&self, state: StateId) -> Result<Option<StateId>, DispatchError> {
+        Ok(self.get(state)?.initial())
+    }
+
+    fn transition(
+        &self,
+        state: StateId,
+        context: &mut C,
+        event: &E,
+    ) -> Result<Step<StateId, C, E>, DispatchError> {
+        Ok(match self.get(state)?.transition(context, event) {
+            IndexedTransition::<C, E>::Unknown => Step::Unknown,
+            IndexedTransition::<C, E>::Internal(behavior) => Step::Internal(behavior),
+            IndexedTransition::<C, E>::Local(target_state, behavior) => {
+                Step::Local(target_state, behavior)
+            }
+            IndexedTransition::<C, E>::External(target_state, behavior) => {
+                Step::External(target_state, behavior)
+            }
+        })
+    }
+
+    fn entry(&self, state: StateId, context: &mut C) -> Result<(), DispatchError> {
+        self.get(state)?.entry(context);
+
+        Ok(())
+    }
+
+    fn exit(&self, state: StateId, context: &mut C) -> Result<(), DispatchError> {
+        self.get(state)?.exit(context);
+
+        Ok(())
+    }
+}
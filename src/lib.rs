@@ -1,161 +1,2645 @@
 #![no_std]
 
-const MAX_DEPTH: usize = 8;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+use core::any::Any;
+use core::fmt;
+
+mod hierarchy;
+mod indexed;
+mod tree;
+
+pub use indexed::{IndexedState, IndexedStateMachine, IndexedTransition, StateId};
+pub use tree::{StateTree, StaticStateMachine, StaticTransition};
+
+#[cfg(feature = "macros")]
+pub use hsm_macros::{behavior, hsm, State};
 
 type Behavior<C, E> = Option<fn(&mut C, &E)>;
 
+type InitialBehavior<C> = Option<fn(&mut C)>;
+
+#[cfg(feature = "alloc")]
+type BoxedBehavior<C, E> = Box<dyn FnOnce(&mut C, &E)>;
+
+type Guard<C, E> = fn(&C, &E) -> bool;
+
+pub type Initial<C, E> = Option<(&'static dyn State<C, E>, InitialBehavior<C>)>;
+
+pub type Completion<C, E> = Option<(&'static dyn State<C, E>, InitialBehavior<C>)>;
+
 pub enum Transition<C: 'static, E: 'static> {
     Unknown,
     Internal(Behavior<C, E>),
     Local(&'static dyn State<C, E>, Behavior<C, E>),
     External(&'static dyn State<C, E>, Behavior<C, E>),
+    History(&'static dyn State<C, E>, Behavior<C, E>),
+    Choice(&'static Choice<C, E>, Behavior<C, E>),
+    Junction(&'static Junction<C, E>, Behavior<C, E>),
+    Fork(&'static Fork<C, E>, Behavior<C, E>),
+    Join(&'static Join<C, E>, Behavior<C, E>),
+    Entry(&'static EntryPoint<C, E>, Behavior<C, E>),
+    Exit(&'static ExitPoint<C, E>, Behavior<C, E>),
+    Error(DispatchError),
+    #[cfg(feature = "alloc")]
+    Effect(Box<Transition<C, E>>, BoxedBehavior<C, E>),
 }
 
-pub trait State<C: 'static, E: 'static> {
+#[cfg(feature = "alloc")]
+impl<C: 'static, E: 'static> Transition<C, E> {
+    pub fn with(self, effect: impl FnOnce(&mut C, &E) + 'static) -> Self {
+        Transition::<C, E>::Effect(Box::new(self), Box::new(effect))
+    }
+}
+
+pub struct Branch<C: 'static, E: 'static> {
+    guard: Option<Guard<C, E>>,
+    target: &'static (dyn State<C, E> + Sync),
+    behavior: Behavior<C, E>,
+}
+
+impl<C: 'static, E: 'static> Branch<C, E> {
+    pub const fn new(
+        guard: Guard<C, E>,
+        target: &'static (dyn State<C, E> + Sync),
+        behavior: Behavior<C, E>,
+    ) -> Self {
+        Self {
+            guard: Some(guard),
+            target,
+            behavior,
+        }
+    }
+
+    pub const fn otherwise(
+        target: &'static (dyn State<C, E> + Sync),
+        behavior: Behavior<C, E>,
+    ) -> Self {
+        Self {
+            guard: None,
+            target,
+            behavior,
+        }
+    }
+
+    fn select(
+        branches: &'static [Branch<C, E>],
+        context: &C,
+        event: &E,
+    ) -> Result<&'static Branch<C, E>, DispatchError> {
+        branches
+            .iter()
+            .find(|branch| branch.guard.is_none_or(|guard| guard(context, event)))
+            .ok_or(DispatchError::NoEnabledBranch)
+    }
+}
+
+/// Guards are evaluated after the source has been exited, so they see the effects of exit
+/// actions and of the transition behavior. A choice with no branch enabled beforehand fails
+/// with `NoEnabledBranch` and leaves the machine unchanged, and so does a choice with any
+/// branch target that cannot be entered, whatever its guard. If the exit or the behavior
+/// then disables every branch, the machine settles into the default child of the choice
+/// parent.
+pub struct Choice<C: 'static, E: 'static> {
+    parent: &'static (dyn State<C, E> + Sync),
+    branches: &'static [Branch<C, E>],
+}
+
+impl<C: 'static, E: 'static> Choice<C, E> {
+    pub const fn new(
+        parent: &'static (dyn State<C, E> + Sync),
+        branches: &'static [Branch<C, E>],
+    ) -> Self {
+        Self { parent, branches }
+    }
+}
+
+pub struct Junction<C: 'static, E: 'static> {
+    branches: &'static [Branch<C, E>],
+}
+
+impl<C: 'static, E: 'static> Junction<C, E> {
+    pub const fn new(branches: &'static [Branch<C, E>]) -> Self {
+        Self { branches }
+    }
+}
+
+pub struct Fork<C: 'static, E: 'static> {
+    composite: &'static (dyn State<C, E> + Sync),
+    targets: &'static [&'static (dyn State<C, E> + Sync)],
+}
+
+impl<C: 'static, E: 'static> Fork<C, E> {
+    pub const fn new(
+        composite: &'static (dyn State<C, E> + Sync),
+        targets: &'static [&'static (dyn State<C, E> + Sync)],
+    ) -> Self {
+        Self { composite, targets }
+    }
+}
+
+pub struct Join<C: 'static, E: 'static> {
+    sources: &'static [&'static (dyn State<C, E> + Sync)],
+    target: &'static (dyn State<C, E> + Sync),
+}
+
+impl<C: 'static, E: 'static> Join<C, E> {
+    pub const fn new(
+        sources: &'static [&'static (dyn State<C, E> + Sync)],
+        target: &'static (dyn State<C, E> + Sync),
+    ) -> Self {
+        Self { sources, target }
+    }
+}
+
+pub struct EntryPoint<C: 'static, E: 'static> {
+    composite: &'static (dyn State<C, E> + Sync),
+    target: &'static (dyn State<C, E> + Sync),
+}
+
+impl<C: 'static, E: 'static> EntryPoint<C, E> {
+    pub const fn new(
+        composite: &'static (dyn State<C, E> + Sync),
+        target: &'static (dyn State<C, E> + Sync),
+    ) -> Self {
+        Self { composite, target }
+    }
+}
+
+pub struct ExitPoint<C: 'static, E: 'static> {
+    composite: &'static (dyn State<C, E> + Sync),
+    target: &'static (dyn State<C, E> + Sync),
+}
+
+impl<C: 'static, E: 'static> ExitPoint<C, E> {
+    pub const fn new(
+        composite: &'static (dyn State<C, E> + Sync),
+        target: &'static (dyn State<C, E> + Sync),
+    ) -> Self {
+        Self { composite, target }
+    }
+}
+
+pub struct FinalState<C: 'static, E: 'static> {
+    parent: &'static (dyn State<C, E> + Sync),
+}
+
+impl<C: 'static, E: 'static> FinalState<C, E> {
+    pub const fn new(parent: &'static (dyn State<C, E> + Sync)) -> Self {
+        Self { parent }
+    }
+}
+
+impl<C: 'static, E: 'static> State<C, E> for FinalState<C, E> {
     fn parent(&self) -> Option<&'static dyn State<C, E>> {
-        None
+        Some(self.parent)
     }
 
-    fn entry(&self, _context: &mut C) {}
+    fn is_final(&self) -> bool {
+        true
+    }
+}
 
-    fn transition(&self, _context: &mut C, _event: &E) -> Transition<C, E> {
-        Transition::<C, E>::Unknown
+pub struct EventQueue<E, const N: usize> {
+    events: [Option<E>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<E, const N: usize> EventQueue<E, N> {
+    pub fn new() -> Self {
+        Self {
+            events: core::array::from_fn(|_| None),
+            head: 0,
+            len: 0,
+        }
     }
 
-    fn exit(&self, _context: &mut C) {}
+    pub fn post(&mut self, event: E) -> Result<(), E> {
+        if self.len == N {
+            return Err(event);
+        }
+
+        self.events[(self.head + self.len) % N] = Some(event);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<E> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let event = self.events[self.head].take();
+
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+
+        event
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
 }
 
-pub struct StateMachine<C: 'static, E: 'static> {
-    active_state: &'static dyn State<C, E>,
+impl<E, const N: usize> Default for EventQueue<E, N> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl<C: 'static, E: 'static> StateMachine<C, E> {
-    pub fn new(initial_state: &'static dyn State<C, E>) -> Self {
+pub trait Clock {
+    fn now(&self) -> u64;
+}
+
+pub struct Timeout<E> {
+    delay: u64,
+    periodic: bool,
+    event: fn() -> E,
+}
+
+impl<E> Timeout<E> {
+    pub const fn after(delay: u64, event: fn() -> E) -> Self {
         Self {
-            active_state: initial_state,
+            delay,
+            periodic: false,
+            event,
         }
     }
 
-    pub fn active(&self) -> &'static dyn State<C, E> {
-        self.active_state
+    pub const fn every(period: u64, event: fn() -> E) -> Self {
+        Self {
+            delay: period,
+            periodic: true,
+            event,
+        }
     }
+}
 
-    pub fn dispatch(&mut self, context: &mut C, event: &E) {
-        let mut transition = Transition::<C, E>::Unknown;
-        let mut effective_state = self.active_state;
+pub trait Mailbox<E> {
+    fn next_event(&mut self) -> Option<E>;
+}
 
-        while let Transition::<C, E>::Unknown = transition {
-            transition = effective_state.transition(context, event);
+impl<E, const N: usize> Mailbox<E> for EventQueue<E, N> {
+    fn next_event(&mut self) -> Option<E> {
+        self.pop()
+    }
+}
 
-            match effective_state.parent() {
-                Some(parent_state) => effective_state = parent_state,
-                None => break,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryKind {
+    Shallow,
+    Deep,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DispatchOutcome {
+    Handled,
+    Transitioned,
+    Deferred,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DispatchError {
+    NotStarted,
+    AlreadyStarted,
+    UnhandledEvent,
+    DepthExceeded,
+    DisjointStates,
+    NoEnabledBranch,
+    WidthExceeded,
+    Terminated,
+    DeferralOverflow,
+    InvalidEntryPoint,
+    InvalidExitPoint,
+    InvalidState,
+    HistoryOverflow,
+    InvalidInitialState,
+    MissingLocal,
+    TimerOverflow,
+    LocalOverflow,
+}
+
+impl fmt::Display for DispatchError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            DispatchError::NotStarted => "State machine has not been started!",
+            DispatchError::AlreadyStarted => "State machine has already been started!",
+            DispatchError::UnhandledEvent => "Unhandled event passed through root state!",
+            DispatchError::DepthExceeded => "State tree depth limit exceeded!",
+            DispatchError::DisjointStates => "Common ancestor has not been found!",
+            DispatchError::NoEnabledBranch => "No branch of the choice is enabled!",
+            DispatchError::WidthExceeded => "Active configuration width exceeded!",
+            DispatchError::Terminated => "State machine has terminated!",
+            DispatchError::DeferralOverflow => "Deferred event capacity exceeded!",
+            DispatchError::InvalidEntryPoint => {
+                "Entry point target is outside of its composite state!"
             }
-        }
+            DispatchError::InvalidExitPoint => "Exit point is used outside of its composite state!",
+            DispatchError::InvalidState => "State identifier is out of range!",
+            DispatchError::HistoryOverflow => "History capacity exceeded!",
+            DispatchError::InvalidInitialState => {
+                "Initial state is not a child of its composite state!"
+            }
+            DispatchError::MissingLocal => "Local state data is not allocated!",
+            DispatchError::TimerOverflow => "Timer capacity exceeded!",
+            DispatchError::LocalOverflow => "Local state capacity exceeded!",
+        };
 
-        self.traverse(context, event, transition);
+        formatter.write_str(message)
     }
+}
 
-    fn traverse(&mut self, context: &mut C, event: &E, transition: Transition<C, E>) {
-        let source_state = self.active_state;
-        let mut target_state = source_state;
-        let transition_behavior;
-        let external;
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationIssue {
+    Cycle(usize),
+    DepthExceeded(usize),
+    MultipleRoots(usize),
+    Unreachable(usize),
+}
 
-        match transition {
-            Transition::<C, E>::External(state, behavior) => {
-                transition_behavior = behavior;
-                target_state = state;
-                external = true;
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::Cycle(index) => {
+                write!(formatter, "State #{} has a cyclic parent chain!", index)
             }
-            Transition::<C, E>::Local(state, behavior) => {
-                transition_behavior = behavior;
-                target_state = state;
-                external = false;
+            ValidationIssue::DepthExceeded(index) => {
+                write!(formatter, "State #{} exceeds the tree depth limit!", index)
             }
-            Transition::<C, E>::Internal(behavior) => {
-                transition_behavior = behavior;
-                external = false;
+            ValidationIssue::MultipleRoots(index) => {
+                write!(formatter, "State #{} is an additional root state!", index)
             }
-            Transition::<C, E>::Unknown => {
-                panic!("Unhandled event passed through root state!");
+            ValidationIssue::Unreachable(index) => {
+                write!(
+                    formatter,
+                    "State #{} is not reachable from the root state!",
+                    index
+                )
             }
         }
+    }
+}
 
-        let mut sources: [&'static dyn State<C, E>; MAX_DEPTH] = [source_state; MAX_DEPTH];
-        let mut targets: [&'static dyn State<C, E>; MAX_DEPTH] = [target_state; MAX_DEPTH];
+pub struct ValidationReport<'a, C: 'static, E: 'static> {
+    states: &'a [&'static dyn State<C, E>],
+    root_state: Option<&'static dyn State<C, E>>,
+    depth: usize,
+}
 
-        let mut source_depth = 1;
-        let mut target_depth = 1;
+impl<'a, C: 'static, E: 'static> ValidationReport<'a, C, E> {
+    fn new(states: &'a [&'static dyn State<C, E>], depth: usize) -> Self {
+        let root_state = states
+            .iter()
+            .find_map(|state| Self::examine(*state).map(|(root_state, _)| root_state));
 
-        let mut source_top = 0;
-        let mut target_top = 0;
+        Self {
+            states,
+            root_state,
+            depth,
+        }
+    }
 
-        if !core::ptr::eq(source_state, target_state) {
-            let mut topmost_state;
+    pub fn root(&self) -> Option<&'static dyn State<C, E>> {
+        self.root_state
+    }
 
-            topmost_state = sources[0];
+    pub fn is_valid(&self) -> bool {
+        self.issues().next().is_none()
+    }
 
-            while let Some(parent_state) = topmost_state.parent() {
-                if source_depth == MAX_DEPTH {
-                    panic!("State tree depth limit exceeded!");
-                }
+    pub fn issues(&self) -> impl Iterator<Item = ValidationIssue> + '_ {
+        self.states
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, state)| self.check(index, *state))
+    }
 
-                topmost_state = parent_state;
+    fn check(&self, index: usize, state: &'static dyn State<C, E>) -> Option<ValidationIssue> {
+        let (root_state, depth) = match Self::examine(state) {
+            Some(chain) => chain,
+            None => return Some(ValidationIssue::Cycle(index)),
+        };
 
-                sources[source_depth] = topmost_state;
-                source_depth += 1;
+        if !self
+            .root_state
+            .is_some_and(|main_state| core::ptr::eq(main_state, root_state))
+        {
+            if state.parent().is_none() {
+                return Some(ValidationIssue::MultipleRoots(index));
             }
 
-            topmost_state = targets[0];
-
-            while let Some(parent_state) = topmost_state.parent() {
-                if target_depth == MAX_DEPTH {
-                    panic!("State tree depth limit exceeded!");
-                }
+            return Some(ValidationIssue::Unreachable(index));
+        }
 
-                topmost_state = parent_state;
+        if depth > self.depth {
+            return Some(ValidationIssue::DepthExceeded(index));
+        }
 
-                targets[target_depth] = topmost_state;
-                target_depth += 1;
-            }
+        None
+    }
 
-            let mut common_ancestor = None;
+    fn examine(state: &'static dyn State<C, E>) -> Option<(&'static dyn State<C, E>, usize)> {
+        let mut slow_state = state;
+        let mut fast_state = state;
+        let mut depth = 1;
 
-            'outer: for i in 0..source_depth {
-                'inner: for j in 0..target_depth {
-                    if core::ptr::eq(sources[i], targets[j]) {
-                        common_ancestor = Some(sources[i]);
-                        source_top = i;
-                        target_top = j;
-                        break 'outer;
-                    }
+        loop {
+            for _ in 0..2 {
+                match fast_state.parent() {
+                    Some(parent_state) => fast_state = parent_state,
+                    None => return Some((fast_state, depth)),
                 }
+
+                depth += 1;
             }
 
-            common_ancestor.expect("Common ancestor has not been found!");
-        }
+            slow_state = slow_state.parent()?;
 
-        for i in 0..source_top {
-            sources[i].exit(context);
+            if core::ptr::eq(slow_state, fast_state) {
+                return None;
+            }
         }
+    }
+}
 
-        if external {
-            sources[source_top].exit(context);
-        }
+impl<C: 'static, E: 'static> fmt::Debug for ValidationReport<'_, C, E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_list().entries(self.issues()).finish()
+    }
+}
 
-        if let Some(action) = transition_behavior {
-            action(context, event);
+pub trait State<C: 'static, E: 'static> {
+    fn name(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+
+    fn id(&self) -> Option<u32> {
+        None
+    }
+
+    fn parent(&self) -> Option<&'static dyn State<C, E>> {
+        None
+    }
+
+    fn entry(&self, _context: &mut C) {}
+
+    fn initial(&self) -> Initial<C, E> {
+        None
+    }
+
+    fn history(&self) -> Option<HistoryKind> {
+        None
+    }
+
+    fn regions(&self) -> &'static [&'static (dyn State<C, E> + Sync)] {
+        &[]
+    }
+
+    fn is_final(&self) -> bool {
+        false
+    }
+
+    fn completion(&self, _context: &mut C) -> Completion<C, E> {
+        None
+    }
+
+    fn is_complete(&self, _context: &mut C) -> bool {
+        false
+    }
+
+    fn check_entry(&self, _context: &mut C) -> Result<(), DispatchError> {
+        Ok(())
+    }
+
+    fn check_exit(&self, _context: &mut C) -> Result<(), DispatchError> {
+        Ok(())
+    }
+
+    fn defer(&self, _event: &E) -> Option<E> {
+        None
+    }
+
+    fn timeouts(&self) -> &'static [Timeout<E>] {
+        &[]
+    }
+
+    fn scope(&self) -> Option<&dyn Scope<C, E>> {
+        None
+    }
+
+    fn transition(&self, _context: &mut C, _event: &E) -> Transition<C, E> {
+        Transition::<C, E>::Unknown
+    }
+
+    fn exit(&self, _context: &mut C) {}
+}
+
+impl<C: 'static, E: 'static> dyn State<C, E> {
+    pub fn ancestors(&self) -> impl Iterator<Item = &'static dyn State<C, E>> {
+        core::iter::successors(self.parent(), |state| state.parent())
+    }
+
+    pub fn depth(&self) -> usize {
+        self.ancestors().count() + 1
+    }
+
+    pub fn is_descendant_of(&self, state: &'static dyn State<C, E>) -> bool {
+        self.ancestors()
+            .any(|ancestor_state| core::ptr::eq(ancestor_state, state))
+    }
+
+    pub fn is_ancestor_of(&self, state: &'static dyn State<C, E>) -> bool {
+        state
+            .ancestors()
+            .any(|ancestor_state| core::ptr::eq(ancestor_state, self))
+    }
+}
+
+impl<C: 'static, E: 'static> dyn State<C, E> {
+    fn same(&self, other: &dyn State<C, E>) -> bool {
+        match (self.id(), other.id()) {
+            (Some(id), Some(other_id)) => id == other_id,
+            // Vtables are not unique per type and zero-sized statics may share an address, so
+            // anonymous states compare by address and name, which defaults to the type name.
+            _ => {
+                core::ptr::eq(
+                    self as *const Self as *const (),
+                    other as *const dyn State<C, E> as *const (),
+                ) && self.name() == other.name()
+            }
         }
+    }
+}
+
+impl<C: 'static, E: 'static> PartialEq for dyn State<C, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.same(other)
+    }
+}
+
+impl<C: 'static, E: 'static, S: State<C, E>> PartialEq<S> for dyn State<C, E> {
+    fn eq(&self, other: &S) -> bool {
+        self.same(other)
+    }
+}
+
+impl<C: 'static, E: 'static> fmt::Debug for dyn State<C, E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut state = formatter.debug_struct("State");
+        state.field("name", &self.name());
 
-        if external {
-            targets[target_top].entry(context);
+        if let Some(id) = self.id() {
+            state.field("id", &id);
         }
 
-        for j in (0..target_top).rev() {
-            targets[j].entry(context);
+        state.finish()
+    }
+}
+
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub trait StateBehavior<C: 'static, E: 'static> {
+    fn entry(&self, _context: &mut C) {}
+
+    fn transition(&self, _context: &mut C, _event: &E) -> Transition<C, E> {
+        Transition::<C, E>::Unknown
+    }
+
+    fn exit(&self, _context: &mut C) {}
+}
+
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod __private {
+    pub trait DerivedBehavior {}
+}
+
+pub trait Machine<C, E> {
+    fn is_started(&self) -> bool;
+
+    fn is_terminated(&self) -> bool;
+
+    fn check_start(&self, context: &mut C) -> Result<(), DispatchError>;
+
+    fn check_stop(&self, context: &mut C) -> Result<(), DispatchError>;
+
+    fn try_start(&mut self, context: &mut C) -> Result<(), DispatchError>;
+
+    fn try_stop(&mut self, context: &mut C) -> Result<(), DispatchError>;
+
+    fn try_dispatch(
+        &mut self,
+        context: &mut C,
+        event: &E,
+    ) -> Result<DispatchOutcome, DispatchError>;
+}
+
+pub trait Submachine<C: 'static, E: 'static> {
+    type Context: 'static;
+    type Event: 'static;
+    type Machine: Machine<Self::Context, Self::Event>;
+
+    fn parent(&self) -> Option<&'static dyn State<C, E>>;
+
+    fn split<'a>(&self, context: &'a mut C) -> (&'a mut Self::Machine, &'a mut Self::Context);
+
+    fn event(&self, event: &E) -> Option<Self::Event>;
+
+    fn transition(&self, _context: &mut C, _event: &E) -> Transition<C, E> {
+        Transition::<C, E>::Unknown
+    }
+
+    fn completion(&self, _context: &mut C) -> Completion<C, E> {
+        None
+    }
+}
+
+pub struct SubmachineState<A> {
+    adapter: A,
+}
+
+impl<A> SubmachineState<A> {
+    pub const fn new(adapter: A) -> Self {
+        Self { adapter }
+    }
+}
+
+impl<C: 'static, E: 'static, A: Submachine<C, E>> State<C, E> for SubmachineState<A> {
+    fn parent(&self) -> Option<&'static dyn State<C, E>> {
+        self.adapter.parent()
+    }
+
+    fn entry(&self, context: &mut C) {
+        let (machine, inner_context) = self.adapter.split(context);
+
+        if let Err(error) = machine.try_start(inner_context) {
+            panic!("{}", error);
         }
+    }
+
+    fn completion(&self, context: &mut C) -> Completion<C, E> {
+        self.adapter.completion(context)
+    }
+
+    fn is_complete(&self, context: &mut C) -> bool {
+        let (machine, _) = self.adapter.split(context);
+
+        machine.is_terminated()
+    }
+
+    fn check_entry(&self, context: &mut C) -> Result<(), DispatchError> {
+        let (machine, inner_context) = self.adapter.split(context);
+
+        machine.check_start(inner_context)
+    }
+
+    fn check_exit(&self, context: &mut C) -> Result<(), DispatchError> {
+        let (machine, inner_context) = self.adapter.split(context);
+
+        if machine.is_started() {
+            machine.check_stop(inner_context)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn transition(&self, context: &mut C, event: &E) -> Transition<C, E> {
+        let transition = self.adapter.transition(context, event);
+
+        if let Transition::<C, E>::Unknown = transition {
+            if let Some(inner_event) = self.adapter.event(event) {
+                let (machine, inner_context) = self.adapter.split(context);
+
+                match machine.try_dispatch(inner_context, &inner_event) {
+                    Ok(_) => return Transition::<C, E>::Internal(None),
+                    Err(DispatchError::UnhandledEvent | DispatchError::Terminated) => {}
+                    Err(error) => return Transition::<C, E>::Error(error),
+                }
+            }
+        }
+
+        transition
+    }
+
+    fn exit(&self, context: &mut C) {
+        let (machine, inner_context) = self.adapter.split(context);
+
+        if machine.is_started() {
+            if let Err(error) = machine.try_stop(inner_context) {
+                panic!("{}", error);
+            }
+        }
+    }
+}
+
+pub trait Scope<C: 'static, E: 'static> {
+    fn accepts(&self, slot: &dyn Any) -> bool;
+
+    fn entry(&self, context: &mut C, slot: &mut dyn Any);
+
+    fn transition(&self, context: &mut C, slot: &mut dyn Any, event: &E) -> Transition<C, E>;
+
+    fn exit(&self, context: &mut C, slot: &mut dyn Any);
+}
+
+/// Local data of a `ScopedState`, kept in a slot of the machine's storage type `L`.
+///
+/// All scoped states of one machine share that storage, so `Storage` must be `L`; with several
+/// local types it is typically an enum with a variant per `Local`, and `slot` projects the
+/// variant that belongs to this state.
+pub trait Scoped<C: 'static, E: 'static> {
+    type Local: 'static;
+    type Storage: From<Self::Local> + 'static;
+
+    fn parent(&self) -> Option<&'static dyn State<C, E>> {
+        None
+    }
+
+    fn local(&self) -> Self::Local;
+
+    fn slot(storage: &mut Self::Storage) -> Option<&mut Self::Local>;
+
+    fn entry(&self, _context: &mut C, _local: &mut Self::Local) {}
+
+    fn initial(&self) -> Initial<C, E> {
+        None
+    }
+
+    fn transition(
+        &self,
+        _context: &mut C,
+        _local: &mut Self::Local,
+        _event: &E,
+    ) -> Transition<C, E> {
+        Transition::<C, E>::Unknown
+    }
+
+    fn exit(&self, _context: &mut C, _local: &mut Self::Local) {}
+}
+
+pub struct ScopedState<S> {
+    scope: S,
+}
+
+impl<S> ScopedState<S> {
+    pub const fn new(scope: S) -> Self {
+        Self { scope }
+    }
+}
+
+impl<C: 'static, E: 'static, S: Scoped<C, E>> State<C, E> for ScopedState<S> {
+    fn parent(&self) -> Option<&'static dyn State<C, E>> {
+        self.scope.parent()
+    }
+
+    fn initial(&self) -> Initial<C, E> {
+        self.scope.initial()
+    }
+
+    fn scope(&self) -> Option<&dyn Scope<C, E>> {
+        Some(self)
+    }
+}
+
+impl<C: 'static, E: 'static, S: Scoped<C, E>> Scope<C, E> for ScopedState<S> {
+    fn accepts(&self, slot: &dyn Any) -> bool {
+        slot.is::<Option<S::Storage>>()
+    }
+
+    fn entry(&self, context: &mut C, slot: &mut dyn Any) {
+        if let Some(slot) = slot.downcast_mut::<Option<S::Storage>>() {
+            let storage = slot.insert(S::Storage::from(self.scope.local()));
+
+            if let Some(local) = S::slot(storage) {
+                self.scope.entry(context, local);
+            }
+        }
+    }
+
+    fn transition(&self, context: &mut C, slot: &mut dyn Any, event: &E) -> Transition<C, E> {
+        match slot
+            .downcast_mut::<Option<S::Storage>>()
+            .and_then(Option::as_mut)
+            .and_then(S::slot)
+        {
+            Some(local) => self.scope.transition(context, local, event),
+            None => Transition::<C, E>::Error(DispatchError::MissingLocal),
+        }
+    }
+
+    fn exit(&self, context: &mut C, slot: &mut dyn Any) {
+        if let Some(mut storage) = slot
+            .downcast_mut::<Option<S::Storage>>()
+            .and_then(Option::take)
+        {
+            if let Some(local) = S::slot(&mut storage) {
+                self.scope.exit(context, local);
+            }
+        }
+    }
+}
+
+struct Segment<C: 'static, E: 'static> {
+    target_state: &'static dyn State<C, E>,
+    targets: &'static [&'static (dyn State<C, E> + Sync)],
+    external: bool,
+    history: bool,
+}
+
+struct Course<C: 'static, E: 'static, const DEPTH: usize> {
+    common_ancestor: &'static dyn State<C, E>,
+    targets: [&'static dyn State<C, E>; DEPTH],
+    path_len: usize,
+    external: bool,
+}
+
+struct Footprint<C: 'static, E: 'static> {
+    exit: Exit<C, E>,
+    leaves: usize,
+    timers: usize,
+    locals: usize,
+}
+
+impl<C: 'static, E: 'static> Footprint<C, E> {
+    fn new(exit: Exit<C, E>) -> Self {
+        Self {
+            exit,
+            leaves: 0,
+            timers: 0,
+            locals: 0,
+        }
+    }
+}
+
+type Exit<C, E> = Option<(&'static dyn State<C, E>, bool)>;
+
+type HistoryRecord<C, E> = (&'static dyn State<C, E>, &'static dyn State<C, E>);
+
+type Leaf<C, E> = (&'static dyn State<C, E>, bool, bool);
+
+type TimerRecord<C, E> = (&'static dyn State<C, E>, &'static Timeout<E>, u64);
+
+type LocalRecord<C, E, L> = (Option<&'static dyn State<C, E>>, Option<L>);
+
+pub struct StateMachine<
+    C: 'static,
+    E: 'static,
+    const DEPTH: usize = 8,
+    const WIDTH: usize = 4,
+    const DEFERRED: usize = 4,
+    const HISTORY: usize = 8,
+    const TIMERS: usize = 8,
+    L: 'static = (),
+    const LOCALS: usize = 0,
+> {
+    initial_state: &'static dyn State<C, E>,
+    leaves: [Leaf<C, E>; WIDTH],
+    count: usize,
+    cursor: usize,
+    history: [Option<HistoryRecord<C, E>>; HISTORY],
+    deferred: EventQueue<E, DEFERRED>,
+    timers: [Option<TimerRecord<C, E>>; TIMERS],
+    locals: [LocalRecord<C, E, L>; LOCALS],
+    now: u64,
+    started: bool,
+    terminated: bool,
+}
+
+/// A `StateMachine` with `LOCALS` slots of the shared local storage `L`, taking the storage
+/// parameters first so that the capacity limits can keep their defaults.
+pub type ScopedStateMachine<
+    C,
+    E,
+    L,
+    const LOCALS: usize,
+    const DEPTH: usize = 8,
+    const WIDTH: usize = 4,
+    const DEFERRED: usize = 4,
+    const HISTORY: usize = 8,
+    const TIMERS: usize = 8,
+> = StateMachine<C, E, DEPTH, WIDTH, DEFERRED, HISTORY, TIMERS, L, LOCALS>;
+
+impl<
+        C: 'static,
+        E: 'static,
+        const DEPTH: usize,
+        const WIDTH: usize,
+        const DEFERRED: usize,
+        const HISTORY: usize,
+        const TIMERS: usize,
+        L: 'static,
+        const LOCALS: usize,
+    > StateMachine<C, E, DEPTH, WIDTH, DEFERRED, HISTORY, TIMERS, L, LOCALS>
+{
+    pub const DEPTH: usize = DEPTH;
+
+    const DEPTH_CHECK: () = assert!(DEPTH > 0, "State tree depth limit must be positive!");
+
+    const WIDTH_CHECK: () = assert!(
+        WIDTH > 0,
+        "Active configuration width limit must be positive!"
+    );
+
+    pub fn new(initial_state: &'static dyn State<C, E>) -> Self {
+        let () = Self::DEPTH_CHECK;
+        let () = Self::WIDTH_CHECK;
+
+        Self {
+            initial_state,
+            leaves: [(initial_state, false, false); WIDTH],
+            count: 0,
+            cursor: 0,
+            history: [None; HISTORY],
+            deferred: EventQueue::new(),
+            timers: [None; TIMERS],
+            locals: core::array::from_fn(|_| (None, None)),
+            now: 0,
+            started: false,
+            terminated: false,
+        }
+    }
+
+    /// Checks every state of a chart against this machine's `DEPTH`.
+    ///
+    /// `try_start` only checks the initial configuration and a transition only checks the
+    /// branch it takes, so this is the check that the whole declared hierarchy fits. Run it
+    /// once over all states of the chart, e.g. in a test next to the chart definition.
+    pub fn validate<'a>(states: &'a [&'static dyn State<C, E>]) -> ValidationReport<'a, C, E> {
+        let () = Self::DEPTH_CHECK;
+
+        ValidationReport::new(states, DEPTH)
+    }
+
+    pub fn active(&self) -> &'static dyn State<C, E> {
+        if self.count > 0 {
+            self.leaves[0].0
+        } else {
+            self.initial_state
+        }
+    }
+
+    pub fn configuration(&self) -> impl Iterator<Item = &'static dyn State<C, E>> + '_ {
+        self.leaves[..self.count].iter().map(|(state, _, _)| *state)
+    }
+
+    /// Walks from the first leaf of `configuration()` up to the root, i.e. the same state that
+    /// `active()` reports. With orthogonal regions the other leaves are not on this path; walk
+    /// each of them with `configuration()` and `State::ancestors` instead.
+    pub fn active_path(&self) -> impl Iterator<Item = &'static dyn State<C, E>> {
+        let leaf = if self.count > 0 {
+            Some(self.leaves[0].0)
+        } else {
+            None
+        };
+
+        core::iter::successors(leaf, |state| state.parent())
+    }
+
+    /// Length of `active_path()`, so it only measures the first leaf of the configuration.
+    pub fn depth(&self) -> usize {
+        self.active_path().count()
+    }
+
+    pub fn local(&self, state: &'static dyn State<C, E>) -> Option<&L> {
+        self.locals
+            .iter()
+            .find(|(owner_state, _)| owner_state.is_some_and(|owner| core::ptr::eq(owner, state)))
+            .and_then(|(_, local)| local.as_ref())
+    }
+
+    pub fn is_in(&self, state: &'static dyn State<C, E>) -> bool {
+        self.includes(state)
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    pub fn start(&mut self, context: &mut C) {
+        if let Err(error) = self.try_start(context) {
+            panic!("{}", error);
+        }
+    }
+
+    pub fn try_start(&mut self, context: &mut C) -> Result<(), DispatchError> {
+        if self.started {
+            return Err(DispatchError::AlreadyStarted);
+        }
+
+        let mut states: [&'static dyn State<C, E>; DEPTH] = [self.initial_state; DEPTH];
+        let depth = self.survey(context, &mut states)?;
+
+        self.count = 0;
+        self.cursor = 0;
+        self.deferred.clear();
+
+        self.enter_path(context, &states[..depth], &[], false, true);
+        self.started = true;
+        self.terminated = false;
+
+        self.complete(context).map(|_| ())
+    }
+
+    pub fn stop(&mut self, context: &mut C) {
+        if let Err(error) = self.try_stop(context) {
+            panic!("{}", error);
+        }
+    }
+
+    pub fn try_stop(&mut self, context: &mut C) -> Result<(), DispatchError> {
+        if !self.started {
+            return Err(DispatchError::NotStarted);
+        }
+
+        let root_state = self.root();
+
+        self.retains(root_state, true)?;
+        self.releases(context, root_state, true)?;
+
+        self.exit_below(context, root_state, true);
+        self.deferred.clear();
+        self.timers = [None; TIMERS];
+        self.started = false;
+        self.terminated = false;
+
+        Ok(())
+    }
+
+    /// Checks that `try_start` would succeed, without entering any state.
+    pub fn check_start(&self, context: &mut C) -> Result<(), DispatchError> {
+        let mut states: [&'static dyn State<C, E>; DEPTH] = [self.initial_state; DEPTH];
+
+        self.survey(context, &mut states).map(|_| ())
+    }
+
+    /// Checks that `try_stop` would succeed, without exiting any state.
+    pub fn check_stop(&self, context: &mut C) -> Result<(), DispatchError> {
+        if !self.started {
+            return Err(DispatchError::NotStarted);
+        }
+
+        let root_state = self.root();
+
+        self.retains(root_state, true)?;
+        self.releases(context, root_state, true)
+    }
+
+    fn survey(
+        &self,
+        context: &mut C,
+        states: &mut [&'static dyn State<C, E>; DEPTH],
+    ) -> Result<usize, DispatchError> {
+        let depth = Self::ancestors(states)?;
+
+        let mut footprint = Footprint::new(Some((states[depth - 1], true)));
+        self.measure_path(context, &states[..depth], &[], false, true, &mut footprint)?;
+        self.fits(&footprint)?;
+
+        Ok(depth)
+    }
+
+    fn root(&self) -> &'static dyn State<C, E> {
+        let mut root_state = self.leaves[0].0;
+
+        while let Some(parent_state) = root_state.parent() {
+            root_state = parent_state;
+        }
+
+        root_state
+    }
+
+    pub fn dispatch(&mut self, context: &mut C, event: &E) {
+        if let Err(error) = self.try_dispatch(context, event) {
+            panic!("{}", error);
+        }
+    }
+
+    pub fn deferred(&self) -> usize {
+        self.deferred.len()
+    }
+
+    /// Dispatches an event to every region of the active configuration, in leaf order.
+    ///
+    /// Each transition is checked before its source is exited, so an error leaves the region
+    /// that raised it untouched. Regions are not rolled back, though: transitions already
+    /// taken by earlier regions for the same event stay in effect when a later one fails.
+    pub fn try_dispatch(
+        &mut self,
+        context: &mut C,
+        event: &E,
+    ) -> Result<DispatchOutcome, DispatchError> {
+        let outcome = self.step(context, event)?;
+
+        if outcome == DispatchOutcome::Transitioned {
+            self.recall_deferred(context)?;
+        }
+
+        Ok(outcome)
+    }
+
+    fn step(&mut self, context: &mut C, event: &E) -> Result<DispatchOutcome, DispatchError> {
+        if !self.started {
+            return Err(DispatchError::NotStarted);
+        }
+
+        if self.terminated {
+            return Err(DispatchError::Terminated);
+        }
+
+        for leaf in self.leaves[..self.count].iter_mut() {
+            leaf.1 = false;
+        }
+
+        let mut handlers: [Option<&'static dyn State<C, E>>; WIDTH] = [None; WIDTH];
+        let mut handled = 0;
+
+        let mut outcome = None;
+
+        let mut i = 0;
+
+        while i < self.count {
+            let (source_state, visited, _) = self.leaves[i];
+
+            if visited {
+                i += 1;
+                continue;
+            }
+
+            self.leaves[i].1 = true;
+
+            let mut transition = Transition::<C, E>::Unknown;
+            let mut effective_state = source_state;
+
+            while let Transition::<C, E>::Unknown = transition {
+                transition = self.respond(effective_state, context, event);
+
+                if !self.enabled(&transition) {
+                    transition = Transition::<C, E>::Unknown;
+                }
+
+                if let Transition::<C, E>::Unknown = transition {
+                    match effective_state.parent() {
+                        Some(parent_state) if !self.awaits_regions(parent_state, &handlers) => {
+                            effective_state = parent_state
+                        }
+                        _ => break,
+                    }
+                }
+            }
+
+            if let Transition::<C, E>::Unknown = transition {
+                i += 1;
+                continue;
+            }
+
+            if handled < WIDTH {
+                handlers[handled] = Some(effective_state);
+                handled += 1;
+            }
+
+            let step =
+                self.traverse(context, event, effective_state, transition, &mut |_, _| {})?;
+
+            if outcome.is_none() || step == DispatchOutcome::Transitioned {
+                outcome = Some(step);
+            }
+
+            for index in 0..self.count {
+                if !self.leaves[index].2 && self.leaves[index].0.is_complete(context) {
+                    self.leaves[index].2 = true;
+                }
+            }
+
+            if self.complete(context)? {
+                outcome = Some(DispatchOutcome::Transitioned);
+            }
+
+            if self.terminated {
+                break;
+            }
+
+            i = 0;
+        }
+
+        match outcome {
+            Some(outcome) => Ok(outcome),
+            None => self.defer(event),
+        }
+    }
+
+    fn respond(
+        &mut self,
+        state: &'static dyn State<C, E>,
+        context: &mut C,
+        event: &E,
+    ) -> Transition<C, E> {
+        let scope = match state.scope() {
+            Some(scope) => scope,
+            None => return state.transition(context, event),
+        };
+
+        match self.local_mut(state) {
+            Some(slot) => scope.transition(context, slot, event),
+            None => Transition::<C, E>::Error(DispatchError::MissingLocal),
+        }
+    }
+
+    fn local_mut(&mut self, state: &'static dyn State<C, E>) -> Option<&mut Option<L>> {
+        self.locals
+            .iter_mut()
+            .find(|(owner_state, _)| owner_state.is_some_and(|owner| core::ptr::eq(owner, state)))
+            .map(|(_, local)| local)
+    }
+
+    fn enabled(&self, transition: &Transition<C, E>) -> bool {
+        match transition {
+            Transition::<C, E>::Join(join, _) => {
+                join.sources.iter().all(|state| self.includes(*state))
+            }
+            #[cfg(feature = "alloc")]
+            Transition::<C, E>::Effect(transition, _) => self.enabled(transition),
+            _ => true,
+        }
+    }
+
+    fn defer(&mut self, event: &E) -> Result<DispatchOutcome, DispatchError> {
+        for (leaf, _, _) in self.leaves[..self.count].iter() {
+            let mut effective_state = Some(*leaf);
+
+            while let Some(state) = effective_state {
+                if let Some(deferred_event) = state.defer(event) {
+                    return match self.deferred.post(deferred_event) {
+                        Ok(()) => Ok(DispatchOutcome::Deferred),
+                        Err(_) => Err(DispatchError::DeferralOverflow),
+                    };
+                }
+
+                effective_state = state.parent();
+            }
+        }
+
+        Err(DispatchError::UnhandledEvent)
+    }
+
+    fn recall_deferred(&mut self, context: &mut C) -> Result<(), DispatchError> {
+        let mut pending = self.deferred.len();
+
+        while pending > 0 && !self.terminated {
+            pending -= 1;
+
+            let event = match self.deferred.pop() {
+                Some(event) => event,
+                None => break,
+            };
+
+            match self.step(context, &event) {
+                Ok(DispatchOutcome::Transitioned) => pending = self.deferred.len(),
+                Ok(_) | Err(DispatchError::UnhandledEvent) => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn tick(&mut self, context: &mut C, now: u64) {
+        if let Err(error) = self.try_tick(context, now) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Fires every timer that was due when the tick started, each at most once.
+    ///
+    /// Timers armed while handling the tick wait for the next one, and a periodic timer that
+    /// missed several periods fires once and resumes at its first period after `now`. Timers
+    /// are armed relative to the `now` of the latest tick.
+    pub fn try_tick(&mut self, context: &mut C, now: u64) -> Result<(), DispatchError> {
+        self.now = now;
+
+        let mut due = self.timers;
+
+        while let Some(index) = Self::expired(&due, now) {
+            let (state, timeout, deadline) = match (due[index].take(), self.timers[index]) {
+                (Some(expected), Some(timer)) if Self::same_timer(expected, timer) => timer,
+                _ => continue,
+            };
+
+            self.timers[index] = if timeout.periodic {
+                let period = timeout.delay.max(1);
+                let periods = (now - deadline) / period + 1;
+
+                Some((
+                    state,
+                    timeout,
+                    deadline.saturating_add(periods.saturating_mul(period)),
+                ))
+            } else {
+                None
+            };
+
+            match self.try_dispatch(context, &(timeout.event)()) {
+                Ok(_) | Err(DispatchError::UnhandledEvent) => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn poll(&mut self, context: &mut C, clock: &impl Clock) {
+        self.tick(context, clock.now());
+    }
+
+    fn expired(timers: &[Option<TimerRecord<C, E>>; TIMERS], now: u64) -> Option<usize> {
+        timers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.map(|(_, _, deadline)| (i, deadline)))
+            .filter(|(_, deadline)| *deadline <= now)
+            .min_by_key(|(_, deadline)| *deadline)
+            .map(|(i, _)| i)
+    }
+
+    fn same_timer(timer: TimerRecord<C, E>, other_timer: TimerRecord<C, E>) -> bool {
+        core::ptr::eq(timer.0, other_timer.0)
+            && core::ptr::eq(timer.1, other_timer.1)
+            && timer.2 == other_timer.2
+    }
+
+    pub fn process(&mut self, context: &mut C, event: &E)
+    where
+        C: Mailbox<E>,
+    {
+        if let Err(error) = self.try_process(context, event) {
+            panic!("{}", error);
+        }
+    }
+
+    pub fn try_process(
+        &mut self,
+        context: &mut C,
+        event: &E,
+    ) -> Result<DispatchOutcome, DispatchError>
+    where
+        C: Mailbox<E>,
+    {
+        self.try_drain(context)?;
+
+        let outcome = self.try_dispatch(context, event)?;
+
+        match self.try_drain(context)? {
+            Some(DispatchOutcome::Transitioned) => Ok(DispatchOutcome::Transitioned),
+            _ => Ok(outcome),
+        }
+    }
+
+    pub fn try_drain(&mut self, context: &mut C) -> Result<Option<DispatchOutcome>, DispatchError>
+    where
+        C: Mailbox<E>,
+    {
+        let mut outcome = None;
+
+        while let Some(event) = context.next_event() {
+            let step = self.try_dispatch(context, &event)?;
+
+            if outcome.is_none() || step == DispatchOutcome::Transitioned {
+                outcome = Some(step);
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    fn complete(&mut self, context: &mut C) -> Result<bool, DispatchError> {
+        let mut transitioned = false;
+
+        while let Some(index) = self.leaves[..self.count]
+            .iter()
+            .position(|(_, _, completing)| *completing)
+        {
+            let final_state = self.leaves[index].0;
+            self.leaves[index].2 = false;
+
+            let mut composite_state = match final_state.parent() {
+                Some(parent_state) if final_state.is_final() => parent_state,
+                _ => final_state,
+            };
+
+            if let Some(parent_state) = composite_state.parent() {
+                let regions = parent_state.regions();
+
+                if regions.iter().any(|region_state| {
+                    core::ptr::eq::<dyn State<C, E>>(*region_state, composite_state)
+                }) {
+                    if !regions
+                        .iter()
+                        .all(|region_state| self.finished(*region_state))
+                    {
+                        continue;
+                    }
+
+                    for leaf in self.leaves[..self.count].iter_mut() {
+                        if Self::descends(leaf.0, parent_state) {
+                            leaf.2 = false;
+                        }
+                    }
+
+                    composite_state = parent_state;
+                }
+            }
+
+            if composite_state.parent().is_none() {
+                self.terminated = true;
+                return Ok(transitioned);
+            }
+
+            if let Some((target_state, behavior)) = composite_state.completion(context) {
+                let segment = Segment {
+                    target_state,
+                    targets: &[],
+                    external: false,
+                    history: false,
+                };
+
+                let outcome = self.transit(context, composite_state, segment, true, |context| {
+                    if let Some(action) = behavior {
+                        action(context);
+                    }
+                })?;
+
+                transitioned |= outcome == DispatchOutcome::Transitioned;
+            }
+        }
+
+        Ok(transitioned)
+    }
+
+    fn finished(&self, state: &'static dyn State<C, E>) -> bool {
+        self.leaves[..self.count].iter().any(|(leaf, _, _)| {
+            leaf.is_final()
+                && leaf
+                    .parent()
+                    .is_some_and(|parent| core::ptr::eq(parent, state))
+        })
+    }
+
+    fn awaits_regions(
+        &self,
+        state: &'static dyn State<C, E>,
+        handlers: &[Option<&'static dyn State<C, E>>],
+    ) -> bool {
+        if state.regions().is_empty() {
+            return false;
+        }
+
+        let pending = self.leaves[..self.count]
+            .iter()
+            .any(|(leaf, visited, _)| !visited && Self::descends(*leaf, state));
+
+        let handled = handlers
+            .iter()
+            .flatten()
+            .any(|handler| Self::descends(*handler, state));
+
+        pending || handled
+    }
+
+    fn traverse(
+        &mut self,
+        context: &mut C,
+        event: &E,
+        source_state: &'static dyn State<C, E>,
+        transition: Transition<C, E>,
+        effect: &mut dyn FnMut(&mut C, &E),
+    ) -> Result<DispatchOutcome, DispatchError> {
+        let (segment, behaviors) = match transition {
+            Transition::<C, E>::External(state, behavior) => (
+                Segment {
+                    target_state: state,
+                    targets: &[],
+                    external: true,
+                    history: false,
+                },
+                [behavior, None],
+            ),
+            Transition::<C, E>::Local(state, behavior) => (
+                Segment {
+                    target_state: state,
+                    targets: &[],
+                    external: false,
+                    history: false,
+                },
+                [behavior, None],
+            ),
+            Transition::<C, E>::History(state, behavior) => (
+                Segment {
+                    target_state: state,
+                    targets: &[],
+                    external: false,
+                    history: true,
+                },
+                [behavior, None],
+            ),
+            Transition::<C, E>::Internal(behavior) => {
+                Self::perform(context, event, &[behavior]);
+                effect(context, event);
+
+                return Ok(DispatchOutcome::Handled);
+            }
+            Transition::<C, E>::Fork(fork, behavior) => {
+                if !fork
+                    .targets
+                    .iter()
+                    .all(|state| Self::descends(*state, fork.composite))
+                {
+                    return Err(DispatchError::DisjointStates);
+                }
+
+                let overlapping = fork.targets.iter().enumerate().any(|(i, state)| {
+                    fork.targets[..i]
+                        .iter()
+                        .any(|other_state| !Self::orthogonal(*state, *other_state))
+                });
+
+                if overlapping {
+                    return Err(DispatchError::DisjointStates);
+                }
+
+                (
+                    Segment {
+                        target_state: fork.composite,
+                        targets: fork.targets,
+                        external: false,
+                        history: false,
+                    },
+                    [behavior, None],
+                )
+            }
+            Transition::<C, E>::Join(join, behavior) => (
+                Segment {
+                    target_state: join.target,
+                    targets: &[],
+                    external: false,
+                    history: false,
+                },
+                [behavior, None],
+            ),
+            Transition::<C, E>::Entry(entry_point, behavior) => {
+                if core::ptr::eq(entry_point.target, entry_point.composite)
+                    || !Self::descends(entry_point.target, entry_point.composite)
+                {
+                    return Err(DispatchError::InvalidEntryPoint);
+                }
+
+                (
+                    Segment {
+                        target_state: entry_point.target,
+                        targets: &[],
+                        external: false,
+                        history: false,
+                    },
+                    [behavior, None],
+                )
+            }
+            Transition::<C, E>::Exit(exit_point, behavior) => {
+                if !Self::descends(source_state, exit_point.composite)
+                    || Self::descends(exit_point.target, exit_point.composite)
+                {
+                    return Err(DispatchError::InvalidExitPoint);
+                }
+
+                (
+                    Segment {
+                        target_state: exit_point.target,
+                        targets: &[],
+                        external: false,
+                        history: false,
+                    },
+                    [behavior, None],
+                )
+            }
+            Transition::<C, E>::Junction(junction, behavior) => {
+                let branch = Branch::select(junction.branches, context, event)?;
+
+                (
+                    Segment {
+                        target_state: branch.target,
+                        targets: &[],
+                        external: false,
+                        history: false,
+                    },
+                    [behavior, branch.behavior],
+                )
+            }
+            Transition::<C, E>::Choice(choice, behavior) => {
+                Branch::select(choice.branches, context, event)?;
+
+                let segment = Segment {
+                    target_state: choice.parent,
+                    targets: &[],
+                    external: false,
+                    history: false,
+                };
+
+                self.plan(context, source_state, &segment, true)?;
+
+                for branch in choice.branches {
+                    let segment = Segment {
+                        target_state: branch.target,
+                        targets: &[],
+                        external: false,
+                        history: false,
+                    };
+
+                    self.plan(context, source_state, &segment, true)?;
+                }
+
+                self.transit(context, source_state, segment, false, |context| {
+                    Self::perform(context, event, &[behavior]);
+                    effect(context, event);
+                })?;
+
+                let outcome = Branch::select(choice.branches, context, event).and_then(|branch| {
+                    let segment = Segment {
+                        target_state: branch.target,
+                        targets: &[],
+                        external: false,
+                        history: false,
+                    };
+
+                    self.transit(context, choice.parent, segment, true, |context| {
+                        Self::perform(context, event, &[branch.behavior])
+                    })
+                });
+
+                if outcome.is_err() {
+                    self.exit_below(context, choice.parent, false);
+                    self.descend(context, choice.parent, false, false);
+                }
+
+                return match outcome {
+                    Ok(_) | Err(DispatchError::NoEnabledBranch) => {
+                        Ok(DispatchOutcome::Transitioned)
+                    }
+                    Err(error) => Err(error),
+                };
+            }
+            #[cfg(feature = "alloc")]
+            Transition::<C, E>::Effect(transition, action) => {
+                let mut action = Some(action);
+
+                return self.traverse(
+                    context,
+                    event,
+                    source_state,
+                    *transition,
+                    &mut |context, event| {
+                        if let Some(action) = action.take() {
+                            action(context, event);
+                        }
+
+                        effect(context, event);
+                    },
+                );
+            }
+            Transition::<C, E>::Error(error) => {
+                return Err(error);
+            }
+            Transition::<C, E>::Unknown => {
+                return Err(DispatchError::UnhandledEvent);
+            }
+        };
+
+        self.transit(context, source_state, segment, true, |context| {
+            Self::perform(context, event, &behaviors);
+            effect(context, event);
+        })
+    }
+
+    fn perform(context: &mut C, event: &E, behaviors: &[Behavior<C, E>]) {
+        for action in behaviors.iter().flatten() {
+            action(context, event);
+        }
+    }
+
+    fn transit(
+        &mut self,
+        context: &mut C,
+        source_state: &'static dyn State<C, E>,
+        segment: Segment<C, E>,
+        settle: bool,
+        effect: impl FnOnce(&mut C),
+    ) -> Result<DispatchOutcome, DispatchError> {
+        let target_state = segment.target_state;
+
+        if core::ptr::eq(source_state, target_state)
+            && !segment.external
+            && target_state.initial().is_none()
+            && target_state.regions().is_empty()
+        {
+            effect(context);
+
+            return Ok(DispatchOutcome::Handled);
+        }
+
+        let course = self.plan(context, source_state, &segment, settle)?;
+        let path = &course.targets[..course.path_len];
+
+        self.exit_below(context, course.common_ancestor, course.external);
+
+        effect(context);
+
+        if path.is_empty() {
+            if settle {
+                self.enter_towards(context, target_state, segment.targets, segment.history);
+            } else {
+                self.attach(target_state);
+            }
+        } else {
+            self.enter_path(context, path, segment.targets, segment.history, settle);
+        }
+
+        Ok(DispatchOutcome::Transitioned)
+    }
+
+    fn plan(
+        &self,
+        context: &mut C,
+        source_state: &'static dyn State<C, E>,
+        segment: &Segment<C, E>,
+        settle: bool,
+    ) -> Result<Course<C, E, DEPTH>, DispatchError> {
+        let target_state = segment.target_state;
+
+        let mut sources: [&'static dyn State<C, E>; DEPTH] = [source_state; DEPTH];
+        let mut targets: [&'static dyn State<C, E>; DEPTH] = [target_state; DEPTH];
+
+        let source_depth = Self::ancestors(&mut sources)?;
+        let target_depth = Self::ancestors(&mut targets)?;
+
+        let mut common_ancestor = None;
+
+        let mut target_top = 0;
+
+        'outer: for source in sources.iter().take(source_depth) {
+            for (j, target) in targets.iter().enumerate().take(target_depth) {
+                if core::ptr::eq(*source, *target) {
+                    common_ancestor = Some(*source);
+                    target_top = j;
+                    break 'outer;
+                }
+            }
+        }
+
+        let common_ancestor = common_ancestor.ok_or(DispatchError::DisjointStates)?;
+
+        let external = segment.external
+            && (core::ptr::eq(common_ancestor, source_state)
+                || core::ptr::eq(common_ancestor, target_state));
+
+        let path_len = if external { target_top + 1 } else { target_top };
+
+        let mut footprint = Footprint::new(Some((common_ancestor, external)));
+
+        if path_len > 0 {
+            self.measure_path(
+                context,
+                &targets[..path_len],
+                segment.targets,
+                segment.history,
+                settle,
+                &mut footprint,
+            )?;
+        } else if settle {
+            self.measure_towards(
+                context,
+                target_state,
+                segment.targets,
+                segment.history,
+                target_depth,
+                &mut footprint,
+            )?;
+        } else {
+            footprint.leaves += 1;
+        }
+
+        self.fits(&footprint)?;
+        self.retains(common_ancestor, external)?;
+        self.releases(context, common_ancestor, external)?;
+
+        Ok(Course {
+            common_ancestor,
+            targets,
+            path_len,
+            external,
+        })
+    }
+
+    fn fits(&self, footprint: &Footprint<C, E>) -> Result<(), DispatchError> {
+        let mut removed = 0;
+        let mut released = 0;
+        let mut vacated = 0;
+
+        if let Some((top_state, inclusive)) = footprint.exit {
+            removed = self.leaves[..self.count]
+                .iter()
+                .filter(|(leaf, _, _)| Self::descends(*leaf, top_state))
+                .count();
+
+            released = self
+                .timers
+                .iter()
+                .flatten()
+                .filter(|(owner_state, _, _)| {
+                    Self::leaves_scope(*owner_state, top_state, inclusive)
+                })
+                .count();
+
+            vacated = self
+                .locals
+                .iter()
+                .filter_map(|(owner_state, _)| *owner_state)
+                .filter(|owner_state| Self::leaves_scope(*owner_state, top_state, inclusive))
+                .count();
+        }
+
+        if self.count - removed + footprint.leaves > WIDTH {
+            return Err(DispatchError::WidthExceeded);
+        }
+
+        let vacant = self.timers.iter().filter(|slot| slot.is_none()).count();
+
+        if footprint.timers > vacant + released {
+            return Err(DispatchError::TimerOverflow);
+        }
+
+        let vacant = self
+            .locals
+            .iter()
+            .filter(|(owner_state, _)| owner_state.is_none())
+            .count();
+
+        if footprint.locals > vacant + vacated {
+            return Err(DispatchError::LocalOverflow);
+        }
+
+        Ok(())
+    }
+
+    fn leaves_scope(
+        state: &'static dyn State<C, E>,
+        top_state: &'static dyn State<C, E>,
+        inclusive: bool,
+    ) -> bool {
+        Self::descends(state, top_state) && (inclusive || !core::ptr::eq(state, top_state))
+    }
+
+    fn ancestors(states: &mut [&'static dyn State<C, E>; DEPTH]) -> Result<usize, DispatchError> {
+        let mut depth = 1;
+
+        let mut topmost_state = states[0];
+
+        while let Some(parent_state) = topmost_state.parent() {
+            if depth == DEPTH {
+                return Err(DispatchError::DepthExceeded);
+            }
+
+            topmost_state = parent_state;
+
+            states[depth] = topmost_state;
+            depth += 1;
+        }
+
+        Ok(depth)
+    }
+
+    fn descends(state: &'static dyn State<C, E>, ancestor_state: &'static dyn State<C, E>) -> bool {
+        let mut effective_state = Some(state);
+
+        while let Some(parent_state) = effective_state {
+            if core::ptr::eq(parent_state, ancestor_state) {
+                return true;
+            }
+
+            effective_state = parent_state.parent();
+        }
+
+        false
+    }
+
+    fn orthogonal(state: &'static dyn State<C, E>, other_state: &'static dyn State<C, E>) -> bool {
+        if Self::descends(state, other_state) || Self::descends(other_state, state) {
+            return false;
+        }
+
+        let mut effective_state = state.parent();
+
+        while let Some(parent_state) = effective_state {
+            if Self::descends(other_state, parent_state) {
+                return !parent_state.regions().is_empty();
+            }
+
+            effective_state = parent_state.parent();
+        }
+
+        false
+    }
+
+    fn adopts(
+        composite_state: &'static dyn State<C, E>,
+        child_state: &'static dyn State<C, E>,
+    ) -> bool {
+        child_state
+            .parent()
+            .is_some_and(|parent_state| core::ptr::eq(parent_state, composite_state))
+    }
+
+    fn includes(&self, state: &'static dyn State<C, E>) -> bool {
+        self.leaves[..self.count]
+            .iter()
+            .any(|(leaf, _, _)| Self::descends(*leaf, state))
+    }
+
+    fn measure_state(
+        context: &mut C,
+        state: &'static dyn State<C, E>,
+        footprint: &mut Footprint<C, E>,
+    ) -> Result<(), DispatchError> {
+        state.check_entry(context)?;
+
+        footprint.timers += state.timeouts().len();
+
+        if let Some(scope) = state.scope() {
+            let slot: Option<L> = None;
+
+            if !scope.accepts(&slot) {
+                return Err(DispatchError::MissingLocal);
+            }
+
+            footprint.locals += 1;
+        }
+
+        Ok(())
+    }
+
+    fn measure_path(
+        &self,
+        context: &mut C,
+        path: &[&'static dyn State<C, E>],
+        targets: &[&'static (dyn State<C, E> + Sync)],
+        history: bool,
+        settle: bool,
+        footprint: &mut Footprint<C, E>,
+    ) -> Result<(), DispatchError> {
+        let last = path.len() - 1;
+        let state = path[last];
+        let depth = state.depth();
+
+        Self::measure_state(context, state, footprint)?;
+
+        if last == 0 {
+            if settle {
+                return self.measure_towards(context, state, targets, history, depth, footprint);
+            }
+
+            footprint.leaves += 1;
+            return Ok(());
+        }
+
+        let regions = state.regions();
+
+        if regions.is_empty() {
+            return self.measure_path(context, &path[..last], targets, history, settle, footprint);
+        }
+
+        for region_state in regions {
+            if core::ptr::eq::<dyn State<C, E>>(*region_state, path[last - 1]) {
+                self.measure_path(context, &path[..last], targets, history, settle, footprint)?;
+            } else {
+                Self::measure_state(context, *region_state, footprint)?;
+                self.measure_descent(context, *region_state, false, false, depth + 1, footprint)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn measure_towards(
+        &self,
+        context: &mut C,
+        state: &'static dyn State<C, E>,
+        targets: &[&'static (dyn State<C, E> + Sync)],
+        history: bool,
+        depth: usize,
+        footprint: &mut Footprint<C, E>,
+    ) -> Result<(), DispatchError> {
+        if targets.is_empty()
+            || targets
+                .iter()
+                .any(|target| core::ptr::eq::<dyn State<C, E>>(*target, state))
+        {
+            return self.measure_descent(context, state, history, false, depth, footprint);
+        }
+
+        let regions = state.regions();
+
+        if !regions.is_empty() {
+            if depth == DEPTH {
+                return Err(DispatchError::DepthExceeded);
+            }
+
+            for region_state in regions {
+                Self::measure_state(context, *region_state, footprint)?;
+                self.measure_towards(context, *region_state, targets, false, depth + 1, footprint)?;
+            }
+
+            Ok(())
+        } else if let Some(child_state) = Self::towards(state, targets) {
+            if depth == DEPTH {
+                return Err(DispatchError::DepthExceeded);
+            }
+
+            Self::measure_state(context, child_state, footprint)?;
+            self.measure_towards(context, child_state, targets, false, depth + 1, footprint)
+        } else {
+            self.measure_descent(context, state, false, false, depth, footprint)
+        }
+    }
+
+    fn measure_descent(
+        &self,
+        context: &mut C,
+        composite_state: &'static dyn State<C, E>,
+        history: bool,
+        deep: bool,
+        depth: usize,
+        footprint: &mut Footprint<C, E>,
+    ) -> Result<(), DispatchError> {
+        let mut target_state = composite_state;
+        let mut depth = depth;
+
+        let deep = deep || (history && composite_state.history() == Some(HistoryKind::Deep));
+        let mut history = deep || (history && composite_state.history().is_some());
+
+        loop {
+            let regions = target_state.regions();
+
+            if !regions.is_empty() {
+                if depth == DEPTH {
+                    return Err(DispatchError::DepthExceeded);
+                }
+
+                for region_state in regions {
+                    Self::measure_state(context, *region_state, footprint)?;
+                    self.measure_descent(context, *region_state, deep, deep, depth + 1, footprint)?;
+                }
+
+                return Ok(());
+            }
+
+            let recalled_state = if history {
+                self.recollect(target_state, footprint.exit)
+            } else {
+                None
+            };
+
+            history = deep;
+
+            let child_state = match recalled_state {
+                Some(child_state) => child_state,
+                None => match target_state.initial() {
+                    Some((child_state, _)) if Self::adopts(target_state, child_state) => {
+                        child_state
+                    }
+                    Some(_) => return Err(DispatchError::InvalidInitialState),
+                    None => break,
+                },
+            };
+
+            if depth == DEPTH {
+                return Err(DispatchError::DepthExceeded);
+            }
+
+            Self::measure_state(context, child_state, footprint)?;
+
+            target_state = child_state;
+            depth += 1;
+        }
+
+        footprint.leaves += 1;
+
+        Ok(())
+    }
+
+    fn towards(
+        state: &'static dyn State<C, E>,
+        targets: &[&'static (dyn State<C, E> + Sync)],
+    ) -> Option<&'static dyn State<C, E>> {
+        targets.iter().find_map(|target| {
+            let mut effective_state: &'static dyn State<C, E> = *target;
+
+            while let Some(parent_state) = effective_state.parent() {
+                if core::ptr::eq(parent_state, state) {
+                    return Some(effective_state);
+                }
+
+                effective_state = parent_state;
+            }
+
+            None
+        })
+    }
+
+    fn attach(&mut self, state: &'static dyn State<C, E>) {
+        if self.count == WIDTH {
+            panic!("Active configuration width exceeded!");
+        }
+
+        self.leaves
+            .copy_within(self.cursor..self.count, self.cursor + 1);
+        self.leaves[self.cursor] = (state, true, state.is_final());
+
+        self.count += 1;
+        self.cursor += 1;
+    }
+
+    fn detach(&mut self, index: usize) {
+        self.leaves.copy_within(index + 1..self.count, index);
+
+        self.count -= 1;
+    }
+
+    fn exit_below(
+        &mut self,
+        context: &mut C,
+        top_state: &'static dyn State<C, E>,
+        inclusive: bool,
+    ) {
+        let mut cursor = None;
+        let mut exited_state = None;
+
+        let mut i = 0;
+
+        while i < self.count {
+            let leaf_state = self.leaves[i].0;
+
+            if !Self::descends(leaf_state, top_state) {
+                i += 1;
+                continue;
+            }
+
+            self.detach(i);
+            cursor.get_or_insert(i);
+
+            exited_state = None;
+
+            let mut effective_state = leaf_state;
+
+            while !core::ptr::eq(effective_state, top_state) {
+                let shared = self.leaves[..self.count]
+                    .iter()
+                    .any(|(leaf, _, _)| Self::descends(*leaf, effective_state));
+
+                if shared {
+                    break;
+                }
+
+                self.exit_state(context, effective_state, exited_state);
+                exited_state = Some(effective_state);
+
+                match effective_state.parent() {
+                    Some(parent_state) => effective_state = parent_state,
+                    None => break,
+                }
+            }
+        }
+
+        if inclusive {
+            self.exit_state(context, top_state, exited_state);
+        }
+
+        self.cursor = cursor.unwrap_or(self.count);
+    }
+
+    fn enter_state(&mut self, context: &mut C, state: &'static dyn State<C, E>) {
+        match state.scope() {
+            Some(scope) => {
+                let vacant_slot = self
+                    .locals
+                    .iter_mut()
+                    .find(|(owner_state, _)| owner_state.is_none());
+
+                if let Some((owner_state, local)) = vacant_slot {
+                    *owner_state = Some(state);
+                    scope.entry(context, local);
+                }
+            }
+            None => state.entry(context),
+        }
+
+        for timeout in state.timeouts() {
+            if let Some(slot) = self.timers.iter_mut().find(|slot| slot.is_none()) {
+                *slot = Some((state, timeout, self.now.saturating_add(timeout.delay)));
+            }
+        }
+    }
+
+    fn exit_state(
+        &mut self,
+        context: &mut C,
+        state: &'static dyn State<C, E>,
+        child_state: Option<&'static dyn State<C, E>>,
+    ) {
+        match state.scope() {
+            Some(scope) => {
+                for (owner_state, local) in self.locals.iter_mut() {
+                    if owner_state.is_some_and(|owner| core::ptr::eq(owner, state)) {
+                        scope.exit(context, local);
+
+                        *owner_state = None;
+                        *local = None;
+                    }
+                }
+            }
+            None => state.exit(context),
+        }
+
+        for slot in self.timers.iter_mut() {
+            if slot.is_some_and(|(owner_state, _, _)| core::ptr::eq(owner_state, state)) {
+                *slot = None;
+            }
+        }
+
+        if let Some(child_state) = child_state {
+            if Self::keeps_history(state) {
+                self.remember(state, child_state);
+            }
+        }
+    }
+
+    fn keeps_history(state: &'static dyn State<C, E>) -> bool {
+        if state.history().is_some() {
+            return true;
+        }
+
+        let mut effective_state = state.parent();
+
+        while let Some(parent_state) = effective_state {
+            if let Some(HistoryKind::Deep) = parent_state.history() {
+                return true;
+            }
+
+            effective_state = parent_state.parent();
+        }
+
+        false
+    }
+
+    fn retains(
+        &self,
+        top_state: &'static dyn State<C, E>,
+        inclusive: bool,
+    ) -> Result<(), DispatchError> {
+        let mut required = 0;
+
+        for (i, (leaf_state, _, _)) in self.leaves[..self.count].iter().enumerate() {
+            if core::ptr::eq(*leaf_state, top_state) || !Self::descends(*leaf_state, top_state) {
+                continue;
+            }
+
+            let mut effective_state = leaf_state.parent();
+
+            while let Some(state) = effective_state {
+                let top = core::ptr::eq(state, top_state);
+
+                if top && !inclusive {
+                    break;
+                }
+
+                let counted = self.leaves[..i]
+                    .iter()
+                    .any(|(leaf, _, _)| Self::descends(*leaf, state));
+
+                if counted {
+                    break;
+                }
+
+                if Self::keeps_history(state) && self.recall(state).is_none() {
+                    required += 1;
+                }
+
+                if top {
+                    break;
+                }
+
+                effective_state = state.parent();
+            }
+        }
+
+        let vacant = self.history.iter().filter(|slot| slot.is_none()).count();
+
+        if required > vacant {
+            return Err(DispatchError::HistoryOverflow);
+        }
+
+        Ok(())
+    }
+
+    fn releases(
+        &self,
+        context: &mut C,
+        top_state: &'static dyn State<C, E>,
+        inclusive: bool,
+    ) -> Result<(), DispatchError> {
+        for (i, (leaf_state, _, _)) in self.leaves[..self.count].iter().enumerate() {
+            if !Self::descends(*leaf_state, top_state) {
+                continue;
+            }
+
+            let mut effective_state = Some(*leaf_state);
+
+            while let Some(state) = effective_state {
+                let top = core::ptr::eq(state, top_state);
+
+                if top && !inclusive {
+                    break;
+                }
+
+                let counted = self.leaves[..i]
+                    .iter()
+                    .any(|(leaf, _, _)| Self::descends(*leaf, state));
+
+                if counted {
+                    break;
+                }
+
+                state.check_exit(context)?;
+
+                if top {
+                    break;
+                }
+
+                effective_state = state.parent();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn remember(
+        &mut self,
+        composite_state: &'static dyn State<C, E>,
+        child_state: &'static dyn State<C, E>,
+    ) {
+        let mut vacant_slot = None;
+
+        for (i, slot) in self.history.iter().enumerate() {
+            match slot {
+                Some((state, _)) if core::ptr::eq(*state, composite_state) => {
+                    vacant_slot = Some(i);
+                    break;
+                }
+                None if vacant_slot.is_none() => vacant_slot = Some(i),
+                _ => {}
+            }
+        }
+
+        if let Some(i) = vacant_slot {
+            self.history[i] = Some((composite_state, child_state));
+        }
+    }
+
+    fn recall(
+        &self,
+        composite_state: &'static dyn State<C, E>,
+    ) -> Option<&'static dyn State<C, E>> {
+        self.history.iter().find_map(|slot| match slot {
+            Some((state, child_state)) if core::ptr::eq(*state, composite_state) => {
+                Some(*child_state)
+            }
+            _ => None,
+        })
+    }
+
+    fn recollect(
+        &self,
+        composite_state: &'static dyn State<C, E>,
+        exit: Exit<C, E>,
+    ) -> Option<&'static dyn State<C, E>> {
+        if let Some((top_state, inclusive)) = exit {
+            if Self::leaves_scope(composite_state, top_state, inclusive)
+                && Self::keeps_history(composite_state)
+            {
+                let exited_state = self.leaves[..self.count]
+                    .iter()
+                    .rev()
+                    .map(|(leaf, _, _)| *leaf)
+                    .find(|leaf| {
+                        !core::ptr::eq(*leaf, composite_state)
+                            && Self::descends(*leaf, composite_state)
+                    })
+                    .and_then(|leaf| {
+                        core::iter::successors(Some(leaf), |state| state.parent())
+                            .find(|state| Self::adopts(composite_state, *state))
+                    });
+
+                if exited_state.is_some() {
+                    return exited_state;
+                }
+            }
+        }
+
+        self.recall(composite_state)
+    }
+
+    fn enter_path(
+        &mut self,
+        context: &mut C,
+        path: &[&'static dyn State<C, E>],
+        targets: &[&'static (dyn State<C, E> + Sync)],
+        history: bool,
+        settle: bool,
+    ) {
+        let last = path.len() - 1;
+        let state = path[last];
+
+        self.enter_state(context, state);
+
+        if last == 0 {
+            if settle {
+                self.enter_towards(context, state, targets, history);
+            } else {
+                self.attach(state);
+            }
+
+            return;
+        }
+
+        let regions = state.regions();
+
+        if regions.is_empty() {
+            self.enter_path(context, &path[..last], targets, history, settle);
+            return;
+        }
+
+        for region_state in regions {
+            if core::ptr::eq::<dyn State<C, E>>(*region_state, path[last - 1]) {
+                self.enter_path(context, &path[..last], targets, history, settle);
+            } else {
+                self.enter_state(context, *region_state);
+                self.descend(context, *region_state, false, false);
+            }
+        }
+    }
+
+    fn enter_towards(
+        &mut self,
+        context: &mut C,
+        state: &'static dyn State<C, E>,
+        targets: &[&'static (dyn State<C, E> + Sync)],
+        history: bool,
+    ) {
+        if targets.is_empty()
+            || targets
+                .iter()
+                .any(|target| core::ptr::eq::<dyn State<C, E>>(*target, state))
+        {
+            self.descend(context, state, history, false);
+            return;
+        }
+
+        let regions = state.regions();
+
+        if !regions.is_empty() {
+            for region_state in regions {
+                self.enter_state(context, *region_state);
+                self.enter_towards(context, *region_state, targets, false);
+            }
+        } else if let Some(child_state) = Self::towards(state, targets) {
+            self.enter_state(context, child_state);
+            self.enter_towards(context, child_state, targets, false);
+        } else {
+            self.descend(context, state, false, false);
+        }
+    }
+
+    fn descend(
+        &mut self,
+        context: &mut C,
+        composite_state: &'static dyn State<C, E>,
+        history: bool,
+        deep: bool,
+    ) {
+        let mut target_state = composite_state;
+
+        let deep = deep || (history && composite_state.history() == Some(HistoryKind::Deep));
+        let mut history = deep || (history && composite_state.history().is_some());
+
+        loop {
+            let regions = target_state.regions();
+
+            if !regions.is_empty() {
+                for region_state in regions {
+                    self.enter_state(context, *region_state);
+                    self.descend(context, *region_state, deep, deep);
+                }
+
+                return;
+            }
+
+            let recalled_state = if history {
+                self.recall(target_state)
+            } else {
+                None
+            };
+
+            history = deep;
+
+            if let Some(child_state) = recalled_state {
+                self.enter_state(context, child_state);
+
+                target_state = child_state;
+                continue;
+            }
+
+            let (child_state, initial_behavior) = match target_state.initial() {
+                Some(initial) if Self::adopts(target_state, initial.0) => initial,
+                _ => break,
+            };
+
+            if let Some(action) = initial_behavior {
+                action(context);
+            }
+
+            self.enter_state(context, child_state);
+
+            target_state = child_state;
+        }
+
+        self.attach(target_state);
+    }
+}
+
+impl<
+        C: 'static,
+        E: 'static,
+        const DEPTH: usize,
+        const WIDTH: usize,
+        const DEFERRED: usize,
+        const HISTORY: usize,
+        const TIMERS: usize,
+        L: 'static,
+        const LOCALS: usize,
+    > Machine<C, E> for StateMachine<C, E, DEPTH, WIDTH, DEFERRED, HISTORY, TIMERS, L, LOCALS>
+{
+    fn is_started(&self) -> bool {
+        self.started
+    }
+
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    fn check_start(&self, context: &mut C) -> Result<(), DispatchError> {
+        StateMachine::check_start(self, context)
+    }
+
+    fn check_stop(&self, context: &mut C) -> Result<(), DispatchError> {
+        StateMachine::check_stop(self, context)
+    }
+
+    fn try_start(&mut self, context: &mut C) -> Result<(), DispatchError> {
+        StateMachine::try_start(self, context)
+    }
+
+    fn try_stop(&mut self, context: &mut C) -> Result<(), DispatchError> {
+        StateMachine::try_stop(self, context)
+    }
 
-        self.active_state = target_state;
+    fn try_dispatch(
+        &mut self,
+        context: &mut C,
+        event: &E,
+    ) -> Result<DispatchOutcome, DispatchError> {
+        StateMachine::try_dispatch(self, context, event)
     }
 }
@@ -0,0 +1,151 @@
+use core::marker::PhantomData;
+
+use crate::hierarchy::{Hierarchy, Step};
+use crate::{Behavior, DispatchError, DispatchOutcome};
+
+pub enum StaticTransition<S, C, E> {
+    Unknown,
+    Internal(Behavior<C, E>),
+    Local(S, Behavior<C, E>),
+    External(S, Behavior<C, E>),
+}
+
+pub trait StateTree<C, E>: Copy + PartialEq {
+    fn parent(self) -> Option<Self> {
+        None
+    }
+
+    fn entry(self, _context: &mut C) {}
+
+    fn initial(self) -> Option<Self> {
+        None
+    }
+
+    fn transition(self, _context: &mut C, _event: &E) -> StaticTransition<Self, C, E> {
+        StaticTransition::<Self, C, E>::Unknown
+    }
+
+    fn exit(self, _context: &mut C) {}
+}
+
+pub struct StaticStateMachine<S, C, E, const DEPTH: usize = 8> {
+    initial_state: S,
+    active_state: Option<S>,
+    marker: PhantomData<fn(&mut C, &E)>,
+}
+
+impl<S: StateTree<C, E>, C, E, const DEPTH: usize> StaticStateMachine<S, C, E, DEPTH> {
+    const DEPTH_CHECK: () = assert!(DEPTH > 0, "State tree depth limit must be positive!");
+
+    pub const fn new(initial_state: S) -> Self {
+        let () = Self::DEPTH_CHECK;
+
+        Self {
+            initial_state,
+            active_state: None,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn active(&self) -> S {
+        self.active_state.unwrap_or(self.initial_state)
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.active_state.is_some()
+    }
+
+    pub fn start(&mut self, context: &mut C) {
+        if let Err(error) = self.try_start(context) {
+            panic!("{}", error);
+        }
+    }
+
+    pub fn try_start(&mut self, context: &mut C) -> Result<(), DispatchError> {
+        if self.active_state.is_some() {
+            return Err(DispatchError::AlreadyStarted);
+        }
+
+        self.active_state = Some(self.enter_initial(context, self.initial_state)?);
+
+        Ok(())
+    }
+
+    pub fn stop(&mut self, context: &mut C) {
+        if let Err(error) = self.try_stop(context) {
+            panic!("{}", error);
+        }
+    }
+
+    pub fn try_stop(&mut self, context: &mut C) -> Result<(), DispatchError> {
+        let active_state = self.active_state.ok_or(DispatchError::NotStarted)?;
+
+        self.exit_active(context, active_state)?;
+        self.active_state = None;
+
+        Ok(())
+    }
+
+    pub fn dispatch(&mut self, context: &mut C, event: &E) {
+        if let Err(error) = self.try_dispatch(context, event) {
+            panic!("{}", error);
+        }
+    }
+
+    pub fn try_dispatch(
+        &mut self,
+        context: &mut C,
+        event: &E,
+    ) -> Result<DispatchOutcome, DispatchError> {
+        let active_state = self.active_state.ok_or(DispatchError::NotStarted)?;
+
+        let (outcome, leaf_state) = self.react(context, event, active_state)?;
+        self.active_state = Some(leaf_state);
+
+        Ok(outcome)
+    }
+}
+
+impl<S: StateTree<C, E>, C, E, const DEPTH: usize> Hierarchy<C, E, DEPTH>
+    for StaticStateMachine<S, C, E, DEPTH>
+{
+    type Id = S;
+
+    fn parent(&self, state: S) -> Result<Option<S>, DispatchError> {
+        Ok(state.parent())
+    }
+
+    fn initial(&self, state: S) -> Result<Option<S>, DispatchError> {
+        Ok(state.initial())
+    }
+
+    fn transition(
+        &self,
+        state: S,
+        context: &mut C,
+        event: &E,
+    ) -> Result<Step<S, C, E>, DispatchError> {
+        Ok(match state.transition(context, event) {
+            StaticTransition::<S, C, E>::Unknown => Step::Unknown,
+            StaticTransition::<S, C, E>::Internal(behavior) => Step::Internal(behavior),
+            StaticTransition::<S, C, E>::Local(target_state, behavior) => {
+                Step::Local(target_state, behavior)
+            }
+            StaticTransition::<S, C, E>::External(target_state, behavior) => {
+                Step::External(target_state, behavior)
+            }
+        })
+    }
+
+    fn entry(&self, state: S, context: &mut C) -> Result<(), DispatchError> {
+        state.entry(context);
+
+        Ok(())
+    }
+
+    fn exit(&self, state: S, context: &mut C) -> Result<(), DispatchError> {
+        state.exit(context);
+
+        Ok(())
+    }
+}
@@ -1,5 +1,3 @@
-use hsm;
-
 struct Context {
     the_entry: usize,
     internal_action: usize,
@@ -8,28 +6,22 @@ struct Context {
 }
 
 enum Event {
-    Initial,
     Internal,
     External,
 }
 
 type Transition = hsm::Transition<Context, Event>;
 
+type Initial = hsm::Initial<Context, Event>;
+
 type StateMachine = hsm::StateMachine<Context, Event>;
 
 struct RootState;
-struct InitialState;
 struct TheState;
 
-impl hsm::State<Context, Event> for RootState {}
-
-impl hsm::State<Context, Event> for InitialState {
-    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
-        Some(&ROOT_STATE)
-    }
-
-    fn transition(&self, _context: &mut Context, _event: &Event) -> Transition {
-        Transition::Local(&THE_STATE, None)
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&THE_STATE, None))
     }
 }
 
@@ -52,7 +44,6 @@ impl hsm::State<Context, Event> for TheState {
                 context.external_action += 1;
                 Transition::External(&THE_STATE, None)
             }
-            _ => Transition::Unknown,
         }
     }
 
@@ -62,16 +53,10 @@ impl hsm::State<Context, Event> for TheState {
 }
 
 static ROOT_STATE: RootState = RootState;
-static INITIAL_STATE: InitialState = InitialState;
 static THE_STATE: TheState = TheState;
 
 fn create_machine() -> StateMachine {
-    StateMachine::new(&INITIAL_STATE)
-}
-
-fn initial_step(machine: &mut StateMachine, context: &mut Context) {
-    let initial_event = Event::Initial;
-    machine.dispatch(context, &initial_event);
+    StateMachine::new(&ROOT_STATE)
 }
 
 #[test]
@@ -83,9 +68,9 @@ fn startup() {
         the_exit: 0,
     };
     let mut machine = create_machine();
-    assert!(core::ptr::eq(machine.active(), &INITIAL_STATE));
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
 
-    initial_step(&mut machine, &mut context);
+    machine.start(&mut context);
     assert!(core::ptr::eq(machine.active(), &THE_STATE));
 }
 
@@ -108,9 +93,9 @@ fn multi_self() {
         the_exit: 0,
     };
     let mut machine = create_machine();
-    assert!(core::ptr::eq(machine.active(), &INITIAL_STATE));
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
 
-    initial_step(&mut machine, &mut context);
+    machine.start(&mut context);
     assert!(core::ptr::eq(machine.active(), &THE_STATE));
     assert_eq!(context.the_entry, 1);
 
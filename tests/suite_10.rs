@@ -0,0 +1,376 @@
+struct Context {
+    connected_entry: usize,
+    up_entry: usize,
+    degraded_entry: usize,
+    idle_entry: usize,
+    open_entry: usize,
+    connected_exit: usize,
+    degraded_exit: usize,
+    open_exit: usize,
+    fork_action: usize,
+    join_action: usize,
+}
+
+enum Event {
+    Connect,
+    Broken,
+    Overlap,
+    Open,
+    Close,
+    Finish,
+    #[cfg(feature = "alloc")]
+    Settle,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+type RegionRef = &'static (dyn hsm::State<Context, Event> + Sync);
+
+struct RootState;
+struct DisconnectedState;
+struct ConnectedState;
+struct LinkRegion;
+struct LinkUpState;
+struct LinkDegradedState;
+struct SessionRegion;
+struct SessionIdleState;
+struct SessionOpenState;
+
+impl RootState {
+    fn fork_action(context: &mut Context, _event: &Event) {
+        context.fork_action += 1;
+    }
+
+    fn join_action(context: &mut Context, _event: &Event) {
+        context.join_action += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&DISCONNECTED_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for DisconnectedState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Connect => Transition::Fork(&CONNECT_FORK, Some(RootState::fork_action)),
+            Event::Broken => Transition::Fork(&BROKEN_FORK, None),
+            Event::Overlap => Transition::Fork(&OVERLAP_FORK, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for ConnectedState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.connected_entry += 1;
+    }
+
+    fn regions(&self) -> &'static [RegionRef] {
+        &CONNECTED_REGIONS
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Finish => Transition::Join(&FINISH_JOIN, Some(RootState::join_action)),
+            #[cfg(feature = "alloc")]
+            Event::Settle => Transition::Join(&FINISH_JOIN, None)
+                .with(|context, _event| context.join_action += 1),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.connected_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for LinkRegion {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&CONNECTED_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&LINK_UP_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for LinkUpState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&LINK_REGION)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.up_entry += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for LinkDegradedState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&LINK_REGION)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.degraded_entry += 1;
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.degraded_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for SessionRegion {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&CONNECTED_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&SESSION_IDLE_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for SessionIdleState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&SESSION_REGION)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.idle_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Open => Transition::Local(&SESSION_OPEN_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for SessionOpenState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&SESSION_REGION)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.open_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Close => Transition::Local(&SESSION_IDLE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.open_exit += 1;
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static DISCONNECTED_STATE: DisconnectedState = DisconnectedState;
+static CONNECTED_STATE: ConnectedState = ConnectedState;
+static LINK_REGION: LinkRegion = LinkRegion;
+static LINK_UP_STATE: LinkUpState = LinkUpState;
+static LINK_DEGRADED_STATE: LinkDegradedState = LinkDegradedState;
+static SESSION_REGION: SessionRegion = SessionRegion;
+static SESSION_IDLE_STATE: SessionIdleState = SessionIdleState;
+static SESSION_OPEN_STATE: SessionOpenState = SessionOpenState;
+
+static CONNECTED_REGIONS: [RegionRef; 2] = [&LINK_REGION, &SESSION_REGION];
+
+static CONNECT_FORK: hsm::Fork<Context, Event> = hsm::Fork::new(
+    &CONNECTED_STATE,
+    &[&LINK_DEGRADED_STATE, &SESSION_OPEN_STATE],
+);
+
+static BROKEN_FORK: hsm::Fork<Context, Event> = hsm::Fork::new(
+    &CONNECTED_STATE,
+    &[&SESSION_OPEN_STATE, &DISCONNECTED_STATE],
+);
+
+static OVERLAP_FORK: hsm::Fork<Context, Event> = hsm::Fork::new(
+    &CONNECTED_STATE,
+    &[&SESSION_IDLE_STATE, &LINK_UP_STATE, &SESSION_OPEN_STATE],
+);
+
+static FINISH_JOIN: hsm::Join<Context, Event> = hsm::Join::new(
+    &[&LINK_DEGRADED_STATE, &SESSION_OPEN_STATE],
+    &DISCONNECTED_STATE,
+);
+
+fn create_context() -> Context {
+    Context {
+        connected_entry: 0,
+        up_entry: 0,
+        degraded_entry: 0,
+        idle_entry: 0,
+        open_entry: 0,
+        connected_exit: 0,
+        degraded_exit: 0,
+        open_exit: 0,
+        fork_action: 0,
+        join_action: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+fn assert_configuration(
+    machine: &StateMachine,
+    states: &[&'static dyn hsm::State<Context, Event>],
+) {
+    assert_eq!(machine.configuration().count(), states.len());
+
+    for (active_state, state) in machine.configuration().zip(states) {
+        assert!(core::ptr::eq(active_state, *state));
+    }
+}
+
+#[test]
+fn fork() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert_configuration(&machine, &[&DISCONNECTED_STATE]);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Connect),
+        Ok(hsm::DispatchOutcome::Transitioned)
+    );
+    assert_configuration(&machine, &[&LINK_DEGRADED_STATE, &SESSION_OPEN_STATE]);
+    assert_eq!(context.connected_entry, 1);
+    assert_eq!(context.degraded_entry, 1);
+    assert_eq!(context.open_entry, 1);
+    assert_eq!(context.up_entry, 0);
+    assert_eq!(context.idle_entry, 0);
+    assert_eq!(context.fork_action, 1);
+}
+
+#[test]
+fn disjoint_fork() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Broken),
+        Err(hsm::DispatchError::DisjointStates)
+    );
+    assert_configuration(&machine, &[&DISCONNECTED_STATE]);
+    assert_eq!(context.connected_entry, 0);
+}
+
+#[test]
+fn overlapping_fork() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Overlap),
+        Err(hsm::DispatchError::DisjointStates)
+    );
+    assert_configuration(&machine, &[&DISCONNECTED_STATE]);
+    assert_eq!(context.connected_entry, 0);
+    assert_eq!(context.idle_entry, 0);
+    assert_eq!(context.open_entry, 0);
+}
+
+#[test]
+fn pending_join() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Connect);
+    machine.dispatch(&mut context, &Event::Close);
+    assert_configuration(&machine, &[&LINK_DEGRADED_STATE, &SESSION_IDLE_STATE]);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Finish),
+        Err(hsm::DispatchError::UnhandledEvent)
+    );
+    assert_configuration(&machine, &[&LINK_DEGRADED_STATE, &SESSION_IDLE_STATE]);
+    assert_eq!(context.join_action, 0);
+
+    machine.dispatch(&mut context, &Event::Open);
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Finish),
+        Ok(hsm::DispatchOutcome::Transitioned)
+    );
+    assert_configuration(&machine, &[&DISCONNECTED_STATE]);
+    assert_eq!(context.join_action, 1);
+}
+
+#[test]
+fn multi_join() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Connect);
+        assert_configuration(&machine, &[&LINK_DEGRADED_STATE, &SESSION_OPEN_STATE]);
+
+        machine.dispatch(&mut context, &Event::Finish);
+        assert_configuration(&machine, &[&DISCONNECTED_STATE]);
+        assert_eq!(context.degraded_exit, i + 1);
+        assert_eq!(context.open_exit, i + 1);
+        assert_eq!(context.connected_exit, i + 1);
+        assert_eq!(context.join_action, i + 1);
+    }
+
+    assert_eq!(context.up_entry, 0);
+    assert_eq!(context.idle_entry, 0);
+    assert_eq!(context.fork_action, 1000);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn pending_effect_join() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Connect);
+        machine.dispatch(&mut context, &Event::Close);
+
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Settle),
+            Err(hsm::DispatchError::UnhandledEvent)
+        );
+        assert_configuration(&machine, &[&LINK_DEGRADED_STATE, &SESSION_IDLE_STATE]);
+        assert_eq!(context.join_action, i);
+
+        machine.dispatch(&mut context, &Event::Open);
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Settle),
+            Ok(hsm::DispatchOutcome::Transitioned)
+        );
+        assert_configuration(&machine, &[&DISCONNECTED_STATE]);
+        assert_eq!(context.join_action, i + 1);
+    }
+}
@@ -0,0 +1,273 @@
+struct Context {
+    startup_exit: usize,
+    calibrate_entry: usize,
+    calibrate_exit: usize,
+    run_entry: usize,
+    run_exit: usize,
+    completion_action: usize,
+}
+
+enum Event {
+    Ready,
+    AxisX,
+    AxisY,
+    Halt,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type Completion = hsm::Completion<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+type RegionRef = &'static (dyn hsm::State<Context, Event> + Sync);
+
+type FinalState = hsm::FinalState<Context, Event>;
+
+struct RootState;
+struct StartupState;
+struct InitState;
+struct CalibrateState;
+struct AxisXRegion;
+struct MeasuringXState;
+struct AxisYRegion;
+struct MeasuringYState;
+struct RunState;
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&STARTUP_STATE, None))
+    }
+}
+
+impl StartupState {
+    fn action(context: &mut Context) {
+        context.completion_action += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for StartupState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&INIT_STATE, None))
+    }
+
+    fn completion(&self, _context: &mut Context) -> Completion {
+        Some((&CALIBRATE_STATE, Some(Self::action)))
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.startup_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for InitState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&STARTUP_STATE)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Ready => Transition::Local(&STARTUP_FINAL, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for CalibrateState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.calibrate_entry += 1;
+    }
+
+    fn regions(&self) -> &'static [RegionRef] {
+        &CALIBRATE_REGIONS
+    }
+
+    fn completion(&self, _context: &mut Context) -> Completion {
+        Some((&RUN_STATE, None))
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.calibrate_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for AxisXRegion {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&CALIBRATE_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&MEASURING_X_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for MeasuringXState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&AXIS_X_REGION)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::AxisX => Transition::Local(&AXIS_X_FINAL, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for AxisYRegion {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&CALIBRATE_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&MEASURING_Y_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for MeasuringYState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&AXIS_Y_REGION)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::AxisY => Transition::Local(&AXIS_Y_FINAL, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for RunState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.run_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Halt => Transition::Local(&ROOT_FINAL, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.run_exit += 1;
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static STARTUP_STATE: StartupState = StartupState;
+static INIT_STATE: InitState = InitState;
+static STARTUP_FINAL: FinalState = FinalState::new(&STARTUP_STATE);
+static CALIBRATE_STATE: CalibrateState = CalibrateState;
+static AXIS_X_REGION: AxisXRegion = AxisXRegion;
+static MEASURING_X_STATE: MeasuringXState = MeasuringXState;
+static AXIS_X_FINAL: FinalState = FinalState::new(&AXIS_X_REGION);
+static AXIS_Y_REGION: AxisYRegion = AxisYRegion;
+static MEASURING_Y_STATE: MeasuringYState = MeasuringYState;
+static AXIS_Y_FINAL: FinalState = FinalState::new(&AXIS_Y_REGION);
+static RUN_STATE: RunState = RunState;
+static ROOT_FINAL: FinalState = FinalState::new(&ROOT_STATE);
+
+static CALIBRATE_REGIONS: [RegionRef; 2] = [&AXIS_X_REGION, &AXIS_Y_REGION];
+
+fn create_context() -> Context {
+    Context {
+        startup_exit: 0,
+        calibrate_entry: 0,
+        calibrate_exit: 0,
+        run_entry: 0,
+        run_exit: 0,
+        completion_action: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &INIT_STATE));
+    assert!(!machine.is_terminated());
+}
+
+#[test]
+fn sequencing() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Ready),
+        Ok(hsm::DispatchOutcome::Transitioned)
+    );
+    assert!(core::ptr::eq(machine.active(), &MEASURING_X_STATE));
+    assert_eq!(context.startup_exit, 1);
+    assert_eq!(context.calibrate_entry, 1);
+    assert_eq!(context.completion_action, 1);
+
+    machine.dispatch(&mut context, &Event::AxisX);
+    assert_eq!(machine.configuration().count(), 2);
+    assert!(core::ptr::eq(machine.active(), &AXIS_X_FINAL));
+    assert_eq!(context.calibrate_exit, 0);
+    assert_eq!(context.run_entry, 0);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::AxisX),
+        Err(hsm::DispatchError::UnhandledEvent)
+    );
+
+    machine.dispatch(&mut context, &Event::AxisY);
+    assert!(core::ptr::eq(machine.active(), &RUN_STATE));
+    assert_eq!(machine.configuration().count(), 1);
+    assert_eq!(context.calibrate_exit, 1);
+    assert_eq!(context.run_entry, 1);
+}
+
+#[test]
+fn termination() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    for i in 0..1000 {
+        machine.start(&mut context);
+        machine.dispatch(&mut context, &Event::Ready);
+        machine.dispatch(&mut context, &Event::AxisY);
+        machine.dispatch(&mut context, &Event::AxisX);
+        assert!(core::ptr::eq(machine.active(), &RUN_STATE));
+        assert!(!machine.is_terminated());
+
+        machine.dispatch(&mut context, &Event::Halt);
+        assert!(core::ptr::eq(machine.active(), &ROOT_FINAL));
+        assert!(machine.is_terminated());
+        assert_eq!(context.run_exit, i + 1);
+
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Ready),
+            Err(hsm::DispatchError::Terminated)
+        );
+
+        machine.stop(&mut context);
+        assert!(!machine.is_terminated());
+    }
+}
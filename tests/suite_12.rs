@@ -0,0 +1,187 @@
+struct Context {
+    queue: hsm::EventQueue<Event, 2>,
+    trace: u32,
+    dropped: usize,
+}
+
+enum Event {
+    Begin,
+    Step,
+    Report,
+    Burst,
+    Finish,
+}
+
+impl hsm::Mailbox<Event> for Context {
+    fn next_event(&mut self) -> Option<Event> {
+        self.queue.pop()
+    }
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+struct RootState;
+struct IdleState;
+struct WorkingState;
+
+impl RootState {
+    fn post(context: &mut Context, event: Event) {
+        if context.queue.post(event).is_err() {
+            context.dropped += 1;
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&IDLE_STATE, None))
+    }
+}
+
+impl IdleState {
+    fn begin(context: &mut Context, _event: &Event) {
+        context.trace = context.trace * 10 + 1;
+        RootState::post(context, Event::Step);
+    }
+}
+
+impl hsm::State<Context, Event> for IdleState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Begin => Transition::Local(&WORKING_STATE, Some(Self::begin)),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl WorkingState {
+    fn step(context: &mut Context, _event: &Event) {
+        context.trace = context.trace * 10 + 3;
+    }
+
+    fn report(context: &mut Context, _event: &Event) {
+        context.trace = context.trace * 10 + 4;
+    }
+
+    fn burst(context: &mut Context, _event: &Event) {
+        RootState::post(context, Event::Step);
+        RootState::post(context, Event::Step);
+        RootState::post(context, Event::Step);
+    }
+}
+
+impl hsm::State<Context, Event> for WorkingState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.trace = context.trace * 10 + 2;
+        RootState::post(context, Event::Report);
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Step => Transition::Internal(Some(Self::step)),
+            Event::Report => Transition::Internal(Some(Self::report)),
+            Event::Burst => Transition::Internal(Some(Self::burst)),
+            Event::Finish => Transition::Local(&IDLE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static IDLE_STATE: IdleState = IdleState;
+static WORKING_STATE: WorkingState = WorkingState;
+
+fn create_context() -> Context {
+    Context {
+        queue: hsm::EventQueue::new(),
+        trace: 0,
+        dropped: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn run_to_completion() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+
+    assert_eq!(
+        machine.try_process(&mut context, &Event::Begin),
+        Ok(hsm::DispatchOutcome::Transitioned)
+    );
+    assert!(core::ptr::eq(machine.active(), &WORKING_STATE));
+    assert_eq!(context.trace, 1234);
+    assert!(context.queue.is_empty());
+}
+
+#[test]
+fn multi_process() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for _ in 0..1000 {
+        context.trace = 0;
+
+        machine.process(&mut context, &Event::Begin);
+        assert_eq!(context.trace, 1234);
+
+        machine.process(&mut context, &Event::Finish);
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+        assert_eq!(context.trace, 1234);
+    }
+}
+
+#[test]
+fn queue_overflow() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.process(&mut context, &Event::Begin);
+    context.trace = 0;
+
+    assert_eq!(
+        machine.try_process(&mut context, &Event::Burst),
+        Ok(hsm::DispatchOutcome::Handled)
+    );
+    assert_eq!(context.trace, 33);
+    assert_eq!(context.dropped, 1);
+}
+
+#[test]
+fn unhandled_post() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    assert!(context.queue.post(Event::Step).is_ok());
+    assert_eq!(context.queue.len(), 1);
+
+    assert_eq!(
+        machine.try_process(&mut context, &Event::Begin),
+        Err(hsm::DispatchError::UnhandledEvent)
+    );
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert!(context.queue.is_empty());
+}
@@ -0,0 +1,229 @@
+struct Context {
+    trace: u32,
+    busy_entry: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Event {
+    Work,
+    Done,
+    Request(u32),
+    Flush,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+struct RootState;
+struct IdleState;
+struct BusyState;
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&IDLE_STATE, None))
+    }
+}
+
+impl IdleState {
+    fn request(context: &mut Context, event: &Event) {
+        if let Event::Request(value) = event {
+            context.trace = context.trace * 10 + value;
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for IdleState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Work | Event::Request(0) => Transition::Local(&BUSY_STATE, None),
+            Event::Request(9) => Transition::Error(hsm::DispatchError::InvalidState),
+            Event::Request(_) => Transition::Internal(Some(Self::request)),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for BusyState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.busy_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Done => Transition::Local(&IDLE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn defer(&self, event: &Event) -> Option<Event> {
+        match event {
+            Event::Request(_) | Event::Flush => Some(*event),
+            _ => None,
+        }
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static IDLE_STATE: IdleState = IdleState;
+static BUSY_STATE: BusyState = BusyState;
+
+fn create_context() -> Context {
+    Context {
+        trace: 0,
+        busy_entry: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn deferral() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Work);
+    assert!(core::ptr::eq(machine.active(), &BUSY_STATE));
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Request(1)),
+        Ok(hsm::DispatchOutcome::Deferred)
+    );
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Request(2)),
+        Ok(hsm::DispatchOutcome::Deferred)
+    );
+    assert_eq!(machine.deferred(), 2);
+    assert_eq!(context.trace, 0);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Done),
+        Ok(hsm::DispatchOutcome::Transitioned)
+    );
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(machine.deferred(), 0);
+    assert_eq!(context.trace, 12);
+}
+
+#[test]
+fn redeferral() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Work);
+
+    machine.dispatch(&mut context, &Event::Request(1));
+    machine.dispatch(&mut context, &Event::Request(0));
+    machine.dispatch(&mut context, &Event::Request(2));
+    assert_eq!(machine.deferred(), 3);
+
+    machine.dispatch(&mut context, &Event::Done);
+    assert!(core::ptr::eq(machine.active(), &BUSY_STATE));
+    assert_eq!(machine.deferred(), 1);
+    assert_eq!(context.trace, 1);
+    assert_eq!(context.busy_entry, 2);
+
+    machine.dispatch(&mut context, &Event::Done);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(machine.deferred(), 0);
+    assert_eq!(context.trace, 12);
+}
+
+#[test]
+fn deferral_overflow() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Work);
+
+    for value in 1..5 {
+        machine.dispatch(&mut context, &Event::Request(value));
+    }
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Request(5)),
+        Err(hsm::DispatchError::DeferralOverflow)
+    );
+
+    machine.stop(&mut context);
+    assert_eq!(machine.deferred(), 0);
+}
+
+#[test]
+fn multi_deferral() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        context.trace = 0;
+
+        machine.dispatch(&mut context, &Event::Work);
+        machine.dispatch(&mut context, &Event::Request(3));
+        assert_eq!(context.trace, 0);
+
+        machine.dispatch(&mut context, &Event::Done);
+        assert_eq!(context.trace, 3);
+        assert_eq!(context.busy_entry, i + 1);
+    }
+}
+
+#[test]
+fn failed_recall() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Work);
+    machine.dispatch(&mut context, &Event::Request(9));
+    machine.dispatch(&mut context, &Event::Flush);
+    assert_eq!(machine.deferred(), 2);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Done),
+        Err(hsm::DispatchError::InvalidState)
+    );
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(machine.deferred(), 1);
+
+    for i in 0..1000 {
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Work),
+            Ok(hsm::DispatchOutcome::Transitioned)
+        );
+        assert!(core::ptr::eq(machine.active(), &BUSY_STATE));
+        assert_eq!(context.busy_entry, i + 2);
+
+        if i > 0 {
+            assert_eq!(
+                machine.try_dispatch(&mut context, &Event::Flush),
+                Ok(hsm::DispatchOutcome::Deferred)
+            );
+        }
+
+        assert_eq!(machine.deferred(), 1);
+
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Done),
+            Ok(hsm::DispatchOutcome::Transitioned)
+        );
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+        assert_eq!(machine.deferred(), 0);
+    }
+}
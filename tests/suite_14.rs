@@ -0,0 +1,274 @@
+struct Context {
+    blinks: usize,
+    active_entry: usize,
+    active_exit: usize,
+    pulse_entry: usize,
+}
+
+enum Event {
+    Start,
+    Restart,
+    Stop,
+    Blink,
+    Expire,
+    Pulse,
+}
+
+struct TestClock(u64);
+
+impl hsm::Clock for TestClock {
+    fn now(&self) -> u64 {
+        self.0
+    }
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+type Timeout = hsm::Timeout<Event>;
+
+struct RootState;
+struct IdleState;
+struct ActiveState;
+struct PulseState;
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&IDLE_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for IdleState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Start => Transition::Local(&ACTIVE_STATE, None),
+            Event::Pulse => Transition::Local(&PULSE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl ActiveState {
+    fn blink(context: &mut Context, _event: &Event) {
+        context.blinks += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for ActiveState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.active_entry += 1;
+    }
+
+    fn timeouts(&self) -> &'static [Timeout] {
+        &ACTIVE_TIMEOUTS
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Restart => Transition::External(&ACTIVE_STATE, None),
+            Event::Stop | Event::Expire => Transition::Local(&IDLE_STATE, None),
+            Event::Blink => Transition::Internal(Some(Self::blink)),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.active_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for PulseState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.pulse_entry += 1;
+    }
+
+    fn timeouts(&self) -> &'static [Timeout] {
+        &PULSE_TIMEOUTS
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Pulse => Transition::External(&PULSE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static IDLE_STATE: IdleState = IdleState;
+static ACTIVE_STATE: ActiveState = ActiveState;
+static PULSE_STATE: PulseState = PulseState;
+
+static ACTIVE_TIMEOUTS: [Timeout; 2] = [
+    Timeout::after(500, || Event::Expire),
+    Timeout::every(100, || Event::Blink),
+];
+
+static PULSE_TIMEOUTS: [Timeout; 1] = [Timeout::after(0, || Event::Pulse)];
+
+fn create_context() -> Context {
+    Context {
+        blinks: 0,
+        active_entry: 0,
+        active_exit: 0,
+        pulse_entry: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn timeout() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Start);
+    assert!(core::ptr::eq(machine.active(), &ACTIVE_STATE));
+
+    machine.tick(&mut context, 99);
+    assert_eq!(context.blinks, 0);
+
+    machine.tick(&mut context, 250);
+    assert_eq!(context.blinks, 1);
+    assert!(core::ptr::eq(machine.active(), &ACTIVE_STATE));
+
+    machine.tick(&mut context, 299);
+    assert_eq!(context.blinks, 1);
+
+    machine.tick(&mut context, 500);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(context.blinks, 2);
+    assert_eq!(context.active_exit, 1);
+
+    machine.tick(&mut context, 1000);
+    assert_eq!(context.blinks, 2);
+}
+
+#[test]
+fn cancellation() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.tick(&mut context, 1000);
+
+    machine.dispatch(&mut context, &Event::Start);
+    machine.tick(&mut context, 1150);
+    assert_eq!(context.blinks, 1);
+
+    machine.dispatch(&mut context, &Event::Stop);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+
+    machine.tick(&mut context, 2000);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(context.blinks, 1);
+    assert_eq!(context.active_entry, 1);
+}
+
+#[test]
+fn multi_poll() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    let mut clock = TestClock(0);
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Start);
+
+        clock.0 += 500;
+        machine.poll(&mut context, &clock);
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+        assert_eq!(context.active_exit, i + 1);
+        assert_eq!(context.blinks, i + 1);
+    }
+}
+
+#[test]
+fn tick_deadline() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.tick(&mut context, 10_000);
+    machine.dispatch(&mut context, &Event::Start);
+
+    machine.tick(&mut context, 10_001);
+    assert_eq!(context.blinks, 0);
+    assert!(core::ptr::eq(machine.active(), &ACTIVE_STATE));
+
+    machine.tick(&mut context, 10_100);
+    assert_eq!(context.blinks, 1);
+
+    machine.tick(&mut context, 10_500);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(context.blinks, 2);
+}
+
+#[test]
+fn immediate_timeout() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Pulse);
+    assert!(core::ptr::eq(machine.active(), &PULSE_STATE));
+    assert_eq!(context.pulse_entry, 1);
+
+    for i in 0..1000 {
+        machine.tick(&mut context, 0);
+        assert!(core::ptr::eq(machine.active(), &PULSE_STATE));
+        assert_eq!(context.pulse_entry, i + 2);
+    }
+
+    machine.tick(&mut context, u64::MAX);
+    assert_eq!(context.pulse_entry, 1002);
+}
+
+#[test]
+fn timer_overflow() {
+    let mut context = create_context();
+    let mut machine: hsm::StateMachine<Context, Event, 8, 4, 4, 8, 1> =
+        hsm::StateMachine::new(&ROOT_STATE);
+
+    machine.start(&mut context);
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Start),
+        Err(hsm::DispatchError::TimerOverflow)
+    );
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(context.active_entry, 0);
+
+    let mut machine: hsm::StateMachine<Context, Event, 8, 4, 4, 8, 2> =
+        hsm::StateMachine::new(&ROOT_STATE);
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Start);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Restart);
+        assert!(core::ptr::eq(machine.active(), &ACTIVE_STATE));
+        assert_eq!(context.active_exit, i + 1);
+    }
+
+    machine.tick(&mut context, 100);
+    assert_eq!(context.blinks, 1);
+}
@@ -0,0 +1,202 @@
+struct Context {
+    dialog_entry: usize,
+    welcome_entry: usize,
+    settings_entry: usize,
+    dialog_exit: usize,
+    cancel_action: usize,
+}
+
+enum Event {
+    Open,
+    Configure,
+    Cancel,
+    Broken,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+struct RootState;
+struct OffState;
+struct DialogState;
+struct WelcomeState;
+struct SettingsState;
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&OFF_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for OffState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Open => Transition::Local(&DIALOG_STATE, None),
+            Event::Configure => Transition::Entry(&SETTINGS_ENTRY, None),
+            Event::Cancel => Transition::Exit(&CANCEL_EXIT, None),
+            Event::Broken => Transition::Entry(&BROKEN_ENTRY, None),
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for DialogState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.dialog_entry += 1;
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&WELCOME_STATE, None))
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.dialog_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for WelcomeState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&DIALOG_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.welcome_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Configure => Transition::Local(&SETTINGS_STATE, None),
+            Event::Broken => Transition::Exit(&BROKEN_EXIT, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl SettingsState {
+    fn cancel(context: &mut Context, _event: &Event) {
+        context.cancel_action += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for SettingsState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&DIALOG_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.settings_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Cancel => Transition::Exit(&CANCEL_EXIT, Some(Self::cancel)),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static OFF_STATE: OffState = OffState;
+static DIALOG_STATE: DialogState = DialogState;
+static WELCOME_STATE: WelcomeState = WelcomeState;
+static SETTINGS_STATE: SettingsState = SettingsState;
+
+static SETTINGS_ENTRY: hsm::EntryPoint<Context, Event> =
+    hsm::EntryPoint::new(&DIALOG_STATE, &SETTINGS_STATE);
+
+static BROKEN_ENTRY: hsm::EntryPoint<Context, Event> =
+    hsm::EntryPoint::new(&DIALOG_STATE, &OFF_STATE);
+
+static CANCEL_EXIT: hsm::ExitPoint<Context, Event> = hsm::ExitPoint::new(&DIALOG_STATE, &OFF_STATE);
+
+static BROKEN_EXIT: hsm::ExitPoint<Context, Event> =
+    hsm::ExitPoint::new(&DIALOG_STATE, &SETTINGS_STATE);
+
+fn create_context() -> Context {
+    Context {
+        dialog_entry: 0,
+        welcome_entry: 0,
+        settings_entry: 0,
+        dialog_exit: 0,
+        cancel_action: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn entry_point() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &OFF_STATE));
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Configure),
+        Ok(hsm::DispatchOutcome::Transitioned)
+    );
+    assert!(core::ptr::eq(machine.active(), &SETTINGS_STATE));
+    assert_eq!(context.dialog_entry, 1);
+    assert_eq!(context.settings_entry, 1);
+    assert_eq!(context.welcome_entry, 0);
+}
+
+#[test]
+fn multi_exit_point() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Open);
+        machine.dispatch(&mut context, &Event::Configure);
+        assert!(core::ptr::eq(machine.active(), &SETTINGS_STATE));
+
+        machine.dispatch(&mut context, &Event::Cancel);
+        assert!(core::ptr::eq(machine.active(), &OFF_STATE));
+        assert_eq!(context.dialog_exit, i + 1);
+        assert_eq!(context.cancel_action, i + 1);
+    }
+}
+
+#[test]
+fn invalid_points() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Broken),
+        Err(hsm::DispatchError::InvalidEntryPoint)
+    );
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Cancel),
+        Err(hsm::DispatchError::InvalidExitPoint)
+    );
+    assert!(core::ptr::eq(machine.active(), &OFF_STATE));
+
+    machine.dispatch(&mut context, &Event::Open);
+    assert!(core::ptr::eq(machine.active(), &WELCOME_STATE));
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Broken),
+        Err(hsm::DispatchError::InvalidExitPoint)
+    );
+    assert!(core::ptr::eq(machine.active(), &WELCOME_STATE));
+    assert_eq!(context.dialog_exit, 0);
+}
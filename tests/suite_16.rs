@@ -0,0 +1,386 @@
+struct PlayerContext {
+    running_entry: usize,
+    running_exit: usize,
+}
+
+enum PlayerEvent {
+    Toggle,
+    Jam,
+    Finish,
+}
+
+type PlayerMachine = hsm::StateMachine<PlayerContext, PlayerEvent, 2, 1>;
+
+struct Context {
+    player: PlayerMachine,
+    player_context: PlayerContext,
+    idle_entry: usize,
+    idle_exit: usize,
+    autostop: bool,
+    completions: usize,
+}
+
+enum Event {
+    Play,
+    Toggle,
+    Stop,
+    Eject,
+    Jam,
+    Finish,
+    Leave,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+type PlayerTransition = hsm::Transition<PlayerContext, PlayerEvent>;
+
+type PlayerInitial = hsm::Initial<PlayerContext, PlayerEvent>;
+
+struct PlayerRootState;
+struct PlayerStoppedState;
+struct PlayerRunningState;
+struct PlayerBrokenState;
+
+impl hsm::State<PlayerContext, PlayerEvent> for PlayerRootState {
+    fn initial(&self) -> PlayerInitial {
+        Some((&PLAYER_STOPPED_STATE, None))
+    }
+}
+
+impl hsm::State<PlayerContext, PlayerEvent> for PlayerStoppedState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<PlayerContext, PlayerEvent>> {
+        Some(&PLAYER_ROOT_STATE)
+    }
+
+    fn transition(&self, _context: &mut PlayerContext, event: &PlayerEvent) -> PlayerTransition {
+        match event {
+            PlayerEvent::Toggle => PlayerTransition::Local(&PLAYER_RUNNING_STATE, None),
+            PlayerEvent::Jam => PlayerTransition::Error(hsm::DispatchError::InvalidState),
+            PlayerEvent::Finish => PlayerTransition::Local(&PLAYER_FINAL_STATE, None),
+        }
+    }
+}
+
+impl hsm::State<PlayerContext, PlayerEvent> for PlayerRunningState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<PlayerContext, PlayerEvent>> {
+        Some(&PLAYER_ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut PlayerContext) {
+        context.running_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut PlayerContext, event: &PlayerEvent) -> PlayerTransition {
+        match event {
+            PlayerEvent::Toggle => PlayerTransition::Local(&PLAYER_STOPPED_STATE, None),
+            PlayerEvent::Jam | PlayerEvent::Finish => PlayerTransition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut PlayerContext) {
+        context.running_exit += 1;
+    }
+}
+
+impl hsm::State<PlayerContext, PlayerEvent> for PlayerBrokenState {
+    fn initial(&self) -> PlayerInitial {
+        Some((&PLAYER_RUNNING_STATE, None))
+    }
+}
+
+static PLAYER_ROOT_STATE: PlayerRootState = PlayerRootState;
+static PLAYER_STOPPED_STATE: PlayerStoppedState = PlayerStoppedState;
+static PLAYER_RUNNING_STATE: PlayerRunningState = PlayerRunningState;
+static PLAYER_BROKEN_STATE: PlayerBrokenState = PlayerBrokenState;
+static PLAYER_FINAL_STATE: hsm::FinalState<PlayerContext, PlayerEvent> =
+    hsm::FinalState::new(&PLAYER_ROOT_STATE);
+
+struct RootState;
+struct IdleState;
+struct PlayerAdapter;
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&IDLE_STATE, None))
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Leave => Transition::Local(&IDLE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for IdleState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.idle_entry += 1;
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.idle_exit += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Play => Transition::Local(&PLAYING_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::Submachine<Context, Event> for PlayerAdapter {
+    type Context = PlayerContext;
+    type Event = PlayerEvent;
+    type Machine = PlayerMachine;
+
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn split<'a>(
+        &self,
+        context: &'a mut Context,
+    ) -> (&'a mut PlayerMachine, &'a mut PlayerContext) {
+        (&mut context.player, &mut context.player_context)
+    }
+
+    fn event(&self, event: &Event) -> Option<PlayerEvent> {
+        match event {
+            Event::Toggle => Some(PlayerEvent::Toggle),
+            Event::Jam => Some(PlayerEvent::Jam),
+            Event::Finish => Some(PlayerEvent::Finish),
+            _ => None,
+        }
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Stop => Transition::Local(&IDLE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn completion(&self, context: &mut Context) -> Initial {
+        if context.autostop {
+            Some((&IDLE_STATE, Some(Self::complete)))
+        } else {
+            None
+        }
+    }
+}
+
+impl PlayerAdapter {
+    fn complete(context: &mut Context) {
+        context.completions += 1;
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static IDLE_STATE: IdleState = IdleState;
+static PLAYING_STATE: hsm::SubmachineState<PlayerAdapter> =
+    hsm::SubmachineState::new(PlayerAdapter);
+
+fn create_context() -> Context {
+    Context {
+        player: PlayerMachine::new(&PLAYER_ROOT_STATE),
+        player_context: PlayerContext {
+            running_entry: 0,
+            running_exit: 0,
+        },
+        idle_entry: 0,
+        idle_exit: 0,
+        autostop: false,
+        completions: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert!(!context.player.is_started());
+
+    machine.dispatch(&mut context, &Event::Play);
+    assert!(core::ptr::eq(machine.active(), &PLAYING_STATE));
+    assert!(context.player.is_started());
+    assert!(core::ptr::eq(
+        context.player.active(),
+        &PLAYER_STOPPED_STATE
+    ));
+}
+
+#[test]
+fn forwarding() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Toggle),
+        Err(hsm::DispatchError::UnhandledEvent)
+    );
+
+    machine.dispatch(&mut context, &Event::Play);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Toggle),
+        Ok(hsm::DispatchOutcome::Handled)
+    );
+    assert!(core::ptr::eq(machine.active(), &PLAYING_STATE));
+    assert!(core::ptr::eq(
+        context.player.active(),
+        &PLAYER_RUNNING_STATE
+    ));
+    assert_eq!(context.player_context.running_entry, 1);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Eject),
+        Err(hsm::DispatchError::UnhandledEvent)
+    );
+}
+
+#[test]
+fn multi_submachine() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Play);
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert!(core::ptr::eq(
+            context.player.active(),
+            &PLAYER_RUNNING_STATE
+        ));
+
+        machine.dispatch(&mut context, &Event::Stop);
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+        assert!(!context.player.is_started());
+        assert_eq!(context.player_context.running_exit, i + 1);
+        assert_eq!(context.idle_entry, i + 2);
+    }
+}
+
+#[test]
+fn inner_error() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Play);
+
+    for _ in 0..1000 {
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Jam),
+            Err(hsm::DispatchError::InvalidState)
+        );
+        assert!(core::ptr::eq(machine.active(), &PLAYING_STATE));
+        assert!(core::ptr::eq(
+            context.player.active(),
+            &PLAYER_STOPPED_STATE
+        ));
+
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Jam),
+            Err(hsm::DispatchError::UnhandledEvent)
+        );
+
+        machine.dispatch(&mut context, &Event::Toggle);
+    }
+}
+
+#[test]
+fn terminated_submachine() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Play);
+
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Finish),
+            Ok(hsm::DispatchOutcome::Handled)
+        );
+        assert!(core::ptr::eq(machine.active(), &PLAYING_STATE));
+        assert!(context.player.is_terminated());
+
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Toggle),
+            Err(hsm::DispatchError::UnhandledEvent)
+        );
+
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Leave),
+            Ok(hsm::DispatchOutcome::Transitioned)
+        );
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+        assert!(!context.player.is_started());
+        assert_eq!(context.idle_entry, i + 2);
+        assert_eq!(context.completions, 0);
+    }
+}
+
+#[test]
+fn submachine_completion() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    context.autostop = true;
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Play);
+        machine.dispatch(&mut context, &Event::Toggle);
+        machine.dispatch(&mut context, &Event::Toggle);
+
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Finish),
+            Ok(hsm::DispatchOutcome::Transitioned)
+        );
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+        assert!(!context.player.is_started());
+        assert_eq!(context.completions, i + 1);
+        assert_eq!(context.player_context.running_exit, i + 1);
+    }
+}
+
+#[test]
+fn broken_submachine() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    context.player = PlayerMachine::new(&PLAYER_BROKEN_STATE);
+    machine.start(&mut context);
+
+    for _ in 0..1000 {
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Play),
+            Err(hsm::DispatchError::InvalidInitialState)
+        );
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+        assert!(!context.player.is_started());
+        assert_eq!(context.idle_exit, 0);
+        assert_eq!(context.idle_entry, 1);
+    }
+}
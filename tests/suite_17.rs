@@ -0,0 +1,316 @@
+struct Context {
+    trace: Vec<&'static str>,
+}
+
+enum Event {
+    ExternalSelf,
+    LocalSelf,
+    ExternalDown,
+    LocalDown,
+    ExternalSibling,
+    LocalInner,
+    ExternalInner,
+    Internal,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+struct RootState;
+struct OuterState;
+struct MiddleState;
+struct FirstState;
+struct SecondState;
+struct OtherState;
+
+impl hsm::State<Context, Event> for RootState {
+    fn entry(&self, context: &mut Context) {
+        context.trace.push("root_entry");
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&OUTER_STATE, None))
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.trace.push("root_exit");
+    }
+}
+
+impl OuterState {
+    fn action(context: &mut Context, _event: &Event) {
+        context.trace.push("action");
+    }
+}
+
+impl hsm::State<Context, Event> for OuterState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.trace.push("outer_entry");
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&MIDDLE_STATE, None))
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::ExternalSelf => Transition::External(&OUTER_STATE, Some(Self::action)),
+            Event::LocalSelf => Transition::Local(&OUTER_STATE, Some(Self::action)),
+            Event::ExternalDown => Transition::External(&MIDDLE_STATE, Some(Self::action)),
+            Event::LocalDown => Transition::Local(&MIDDLE_STATE, Some(Self::action)),
+            Event::Internal => Transition::Internal(Some(Self::action)),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.trace.push("outer_exit");
+    }
+}
+
+impl hsm::State<Context, Event> for MiddleState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&OUTER_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.trace.push("middle_entry");
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&FIRST_STATE, None))
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::LocalInner => Transition::Local(&SECOND_STATE, Some(OuterState::action)),
+            Event::ExternalInner => Transition::External(&SECOND_STATE, Some(OuterState::action)),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.trace.push("middle_exit");
+    }
+}
+
+impl hsm::State<Context, Event> for FirstState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&MIDDLE_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.trace.push("first_entry");
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::ExternalSibling => Transition::External(&OTHER_STATE, Some(OuterState::action)),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.trace.push("first_exit");
+    }
+}
+
+impl hsm::State<Context, Event> for SecondState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&MIDDLE_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.trace.push("second_entry");
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.trace.push("second_exit");
+    }
+}
+
+impl hsm::State<Context, Event> for OtherState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.trace.push("other_entry");
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.trace.push("other_exit");
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static OUTER_STATE: OuterState = OuterState;
+static MIDDLE_STATE: MiddleState = MiddleState;
+static FIRST_STATE: FirstState = FirstState;
+static SECOND_STATE: SecondState = SecondState;
+static OTHER_STATE: OtherState = OtherState;
+
+fn create_context() -> Context {
+    Context { trace: Vec::new() }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+fn assert_trace(
+    event: Event,
+    outcome: hsm::DispatchOutcome,
+    active_state: &'static dyn hsm::State<Context, Event>,
+    trace: &[&'static str],
+) {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &FIRST_STATE));
+    context.trace.clear();
+
+    assert_eq!(machine.try_dispatch(&mut context, &event), Ok(outcome));
+    assert!(core::ptr::eq(machine.active(), active_state));
+    assert_eq!(context.trace, trace);
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert_eq!(
+        context.trace,
+        ["root_entry", "outer_entry", "middle_entry", "first_entry"]
+    );
+}
+
+#[test]
+fn external_self_transition() {
+    assert_trace(
+        Event::ExternalSelf,
+        hsm::DispatchOutcome::Transitioned,
+        &FIRST_STATE,
+        &[
+            "first_exit",
+            "middle_exit",
+            "outer_exit",
+            "action",
+            "outer_entry",
+            "middle_entry",
+            "first_entry",
+        ],
+    );
+}
+
+#[test]
+fn local_self_transition() {
+    assert_trace(
+        Event::LocalSelf,
+        hsm::DispatchOutcome::Transitioned,
+        &FIRST_STATE,
+        &[
+            "first_exit",
+            "middle_exit",
+            "action",
+            "middle_entry",
+            "first_entry",
+        ],
+    );
+}
+
+#[test]
+fn external_descendant_transition() {
+    assert_trace(
+        Event::ExternalDown,
+        hsm::DispatchOutcome::Transitioned,
+        &FIRST_STATE,
+        &[
+            "first_exit",
+            "middle_exit",
+            "outer_exit",
+            "action",
+            "outer_entry",
+            "middle_entry",
+            "first_entry",
+        ],
+    );
+}
+
+#[test]
+fn local_descendant_transition() {
+    assert_trace(
+        Event::LocalDown,
+        hsm::DispatchOutcome::Transitioned,
+        &FIRST_STATE,
+        &[
+            "first_exit",
+            "middle_exit",
+            "action",
+            "middle_entry",
+            "first_entry",
+        ],
+    );
+}
+
+#[test]
+fn external_sibling_transition() {
+    assert_trace(
+        Event::ExternalSibling,
+        hsm::DispatchOutcome::Transitioned,
+        &OTHER_STATE,
+        &[
+            "first_exit",
+            "middle_exit",
+            "outer_exit",
+            "action",
+            "other_entry",
+        ],
+    );
+}
+
+#[test]
+fn local_ancestor_handler() {
+    assert_trace(
+        Event::LocalInner,
+        hsm::DispatchOutcome::Transitioned,
+        &SECOND_STATE,
+        &["first_exit", "action", "second_entry"],
+    );
+}
+
+#[test]
+fn external_ancestor_handler() {
+    assert_trace(
+        Event::ExternalInner,
+        hsm::DispatchOutcome::Transitioned,
+        &SECOND_STATE,
+        &[
+            "first_exit",
+            "middle_exit",
+            "action",
+            "middle_entry",
+            "second_entry",
+        ],
+    );
+}
+
+#[test]
+fn internal_transition() {
+    assert_trace(
+        Event::Internal,
+        hsm::DispatchOutcome::Handled,
+        &FIRST_STATE,
+        &["action"],
+    );
+}
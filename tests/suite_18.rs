@@ -0,0 +1,143 @@
+#![cfg(feature = "alloc")]
+
+struct Context {
+    value: u32,
+    total: u32,
+    trace: Vec<&'static str>,
+}
+
+enum Event {
+    Data(&'static str),
+    Add(&'static str),
+    Clear,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+struct RootState;
+struct IdleState;
+struct LoadedState;
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&IDLE_STATE, None))
+    }
+}
+
+impl IdleState {
+    fn action(context: &mut Context, _event: &Event) {
+        context.trace.push("action");
+    }
+}
+
+impl hsm::State<Context, Event> for IdleState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Data(payload) => match payload.parse::<u32>() {
+                Ok(value) => Transition::Local(&LOADED_STATE, Some(Self::action))
+                    .with(move |context, _event| {
+                        context.trace.push("first");
+                        context.value = value;
+                    })
+                    .with(|context, _event| context.trace.push("second")),
+                Err(_) => Transition::Unknown,
+            },
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.trace.push("idle_exit");
+    }
+}
+
+impl hsm::State<Context, Event> for LoadedState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.trace.push("loaded_entry");
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Add(payload) => match payload.parse::<u32>() {
+                Ok(value) => {
+                    Transition::Internal(None).with(move |context, _event| context.total += value)
+                }
+                Err(_) => Transition::Unknown,
+            },
+            Event::Clear => Transition::Local(&IDLE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static IDLE_STATE: IdleState = IdleState;
+static LOADED_STATE: LoadedState = LoadedState;
+
+fn create_context() -> Context {
+    Context {
+        value: 0,
+        total: 0,
+        trace: Vec::new(),
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn captured_effect() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Data("nope")),
+        Err(hsm::DispatchError::UnhandledEvent)
+    );
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Data("42")),
+        Ok(hsm::DispatchOutcome::Transitioned)
+    );
+    assert!(core::ptr::eq(machine.active(), &LOADED_STATE));
+    assert_eq!(context.value, 42);
+    assert_eq!(
+        context.trace,
+        ["idle_exit", "action", "first", "second", "loaded_entry"]
+    );
+}
+
+#[test]
+fn multi_internal_effect() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Data("1"));
+
+    for i in 0..1000 {
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Add("3")),
+            Ok(hsm::DispatchOutcome::Handled)
+        );
+        assert_eq!(context.total, 3 * (i + 1));
+    }
+
+    machine.dispatch(&mut context, &Event::Clear);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+}
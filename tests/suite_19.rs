@@ -0,0 +1,213 @@
+use hsm::{IndexedState, IndexedTransition, StateId};
+
+struct Context {
+    brightness: u32,
+    entries: usize,
+    exits: usize,
+}
+
+enum Event {
+    Toggle,
+    Boost,
+    Reset,
+    Broken,
+}
+
+type Transition = IndexedTransition<Context, Event>;
+
+type StateMachine = hsm::IndexedStateMachine<Context, Event, LampState, 5>;
+
+const ROOT: StateId = StateId(0);
+const OFF: StateId = StateId(1);
+const ON: StateId = StateId(2);
+const DIM: StateId = StateId(3);
+const BRIGHT: StateId = StateId(4);
+
+enum LampState {
+    Root,
+    Off,
+    On,
+    Level(u32),
+}
+
+impl LampState {
+    fn reset(context: &mut Context, _event: &Event) {
+        context.brightness = 0;
+    }
+}
+
+impl IndexedState<Context, Event> for LampState {
+    fn parent(&self) -> Option<StateId> {
+        match self {
+            LampState::Root => None,
+            LampState::Off | LampState::On => Some(ROOT),
+            LampState::Level(_) => Some(ON),
+        }
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.entries += 1;
+
+        if let LampState::Level(brightness) = self {
+            context.brightness = *brightness;
+        }
+    }
+
+    fn initial(&self) -> Option<StateId> {
+        match self {
+            LampState::Root => Some(OFF),
+            LampState::On => Some(DIM),
+            _ => None,
+        }
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match (self, event) {
+            (LampState::Off, Event::Toggle) => Transition::Local(ON, None),
+            (LampState::On, Event::Toggle) => Transition::Local(OFF, Some(Self::reset)),
+            (LampState::On, Event::Reset) => Transition::External(ON, None),
+            (LampState::Level(_), Event::Boost) => Transition::Local(BRIGHT, None),
+            (LampState::Root, Event::Broken) => Transition::Local(StateId(7), None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.exits += 1;
+    }
+}
+
+fn create_context() -> Context {
+    Context {
+        brightness: 0,
+        entries: 0,
+        exits: 0,
+    }
+}
+
+fn create_machine(dim: u32, bright: u32) -> StateMachine {
+    StateMachine::new(
+        [
+            LampState::Root,
+            LampState::Off,
+            LampState::On,
+            LampState::Level(dim),
+            LampState::Level(bright),
+        ],
+        ROOT,
+    )
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine(10, 100);
+    assert_eq!(machine.active(), ROOT);
+    assert!(!machine.is_started());
+
+    machine.start(&mut context);
+    assert_eq!(machine.active(), OFF);
+    assert_eq!(context.entries, 2);
+}
+
+#[test]
+fn instances() {
+    let mut first_context = create_context();
+    let mut first_machine = create_machine(10, 100);
+    let mut second_context = create_context();
+    let mut second_machine = create_machine(20, 200);
+
+    first_machine.start(&mut first_context);
+    second_machine.start(&mut second_context);
+
+    first_machine.dispatch(&mut first_context, &Event::Toggle);
+    second_machine.dispatch(&mut second_context, &Event::Toggle);
+    assert_eq!(first_machine.active(), DIM);
+    assert_eq!(first_context.brightness, 10);
+    assert_eq!(second_context.brightness, 20);
+
+    second_machine.dispatch(&mut second_context, &Event::Boost);
+    assert_eq!(second_machine.active(), BRIGHT);
+    assert_eq!(second_context.brightness, 200);
+    assert_eq!(first_context.brightness, 10);
+}
+
+#[test]
+fn multi_toggle() {
+    let mut context = create_context();
+    let mut machine = create_machine(10, 100);
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Toggle),
+            Ok(hsm::DispatchOutcome::Transitioned)
+        );
+        assert_eq!(machine.active(), DIM);
+
+        machine.dispatch(&mut context, &Event::Boost);
+        assert_eq!(machine.active(), BRIGHT);
+
+        machine.dispatch(&mut context, &Event::Reset);
+        assert_eq!(machine.active(), DIM);
+        assert_eq!(context.brightness, 10);
+
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert_eq!(machine.active(), OFF);
+        assert_eq!(context.brightness, 0);
+        assert_eq!(context.exits, 6 * (i + 1));
+    }
+}
+
+#[test]
+fn errors() {
+    let mut context = create_context();
+    let mut machine = create_machine(10, 100);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Toggle),
+        Err(hsm::DispatchError::NotStarted)
+    );
+
+    machine.start(&mut context);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Boost),
+        Err(hsm::DispatchError::UnhandledEvent)
+    );
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Broken),
+        Err(hsm::DispatchError::InvalidState)
+    );
+    assert_eq!(machine.active(), OFF);
+
+    machine.stop(&mut context);
+    assert!(!machine.is_started());
+    assert_eq!(context.exits, 2);
+}
+
+#[test]
+fn invalid_initial() {
+    let mut context = create_context();
+    let mut machine = StateMachine::new(
+        [
+            LampState::Root,
+            LampState::Off,
+            LampState::On,
+            LampState::Off,
+            LampState::Level(100),
+        ],
+        ROOT,
+    );
+
+    machine.start(&mut context);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Toggle),
+        Err(hsm::DispatchError::InvalidInitialState)
+    );
+    assert_eq!(machine.active(), OFF);
+    assert_eq!(context.entries, 2);
+    assert_eq!(context.exits, 0);
+}
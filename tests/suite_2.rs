@@ -1,5 +1,3 @@
-use hsm;
-
 struct Context {
     first_entry: usize,
     second_entry: usize,
@@ -13,7 +11,6 @@ struct Context {
 }
 
 enum Event {
-    Initial,
     First,
     Second,
     Third,
@@ -23,27 +20,22 @@ enum Event {
 
 type Transition = hsm::Transition<Context, Event>;
 
+type Initial = hsm::Initial<Context, Event>;
+
 type StateMachine = hsm::StateMachine<Context, Event>;
 
 struct RootState;
-struct InitialState;
 struct FirstState;
 struct SecondState;
 struct ThirdState;
 
 impl hsm::State<Context, Event> for RootState {
-    fn transition(&self, _context: &mut Context, _event: &Event) -> Transition {
-        Transition::Internal(None)
-    }
-}
-
-impl hsm::State<Context, Event> for InitialState {
-    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
-        Some(&ROOT_STATE)
+    fn initial(&self) -> Initial {
+        Some((&FIRST_STATE, None))
     }
 
     fn transition(&self, _context: &mut Context, _event: &Event) -> Transition {
-        Transition::Local(&FIRST_STATE, None)
+        Transition::Internal(None)
     }
 }
 
@@ -135,18 +127,12 @@ impl hsm::State<Context, Event> for ThirdState {
 }
 
 static ROOT_STATE: RootState = RootState;
-static INITIAL_STATE: InitialState = InitialState;
 static FIRST_STATE: FirstState = FirstState;
 static SECOND_STATE: SecondState = SecondState;
 static THIRD_STATE: ThirdState = ThirdState;
 
 fn create_machine() -> StateMachine {
-    StateMachine::new(&INITIAL_STATE)
-}
-
-fn initial_step(machine: &mut StateMachine, context: &mut Context) {
-    let initial_event = Event::Initial;
-    machine.dispatch(context, &initial_event);
+    StateMachine::new(&ROOT_STATE)
 }
 
 #[test]
@@ -163,9 +149,9 @@ fn startup() {
         third_exit: 0,
     };
     let mut machine = create_machine();
-    assert!(core::ptr::eq(machine.active(), &INITIAL_STATE));
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
 
-    initial_step(&mut machine, &mut context);
+    machine.start(&mut context);
     assert!(core::ptr::eq(machine.active(), &FIRST_STATE));
 }
 
@@ -208,9 +194,9 @@ fn multi_ladder() {
         third_exit: 0,
     };
     let mut machine = create_machine();
-    assert!(core::ptr::eq(machine.active(), &INITIAL_STATE));
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
 
-    initial_step(&mut machine, &mut context);
+    machine.start(&mut context);
     assert!(core::ptr::eq(machine.active(), &FIRST_STATE));
     assert_eq!(context.first_entry, 1);
 
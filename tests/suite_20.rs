@@ -0,0 +1,330 @@
+struct Retries {
+    count: u32,
+    limit: u32,
+}
+
+enum Locals {
+    Retries(Retries),
+    Buffer([u8; 4]),
+}
+
+impl From<Retries> for Locals {
+    fn from(retries: Retries) -> Self {
+        Locals::Retries(retries)
+    }
+}
+
+impl From<[u8; 4]> for Locals {
+    fn from(buffer: [u8; 4]) -> Self {
+        Locals::Buffer(buffer)
+    }
+}
+
+impl Locals {
+    fn retries(&self) -> Option<&Retries> {
+        match self {
+            Locals::Retries(retries) => Some(retries),
+            _ => None,
+        }
+    }
+
+    fn buffer(&self) -> Option<&[u8; 4]> {
+        match self {
+            Locals::Buffer(buffer) => Some(buffer),
+            _ => None,
+        }
+    }
+}
+
+struct Context {
+    attempts: u32,
+    last_count: u32,
+    flushed: usize,
+}
+
+enum Event {
+    Connect,
+    Fail,
+    Succeed,
+    Receive(u8),
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::ScopedStateMachine<Context, Event, Locals, 1>;
+
+struct RootState;
+struct OfflineState;
+struct ConnectingScope;
+struct OnlineState;
+struct ReceivingScope;
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&OFFLINE_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for OfflineState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Connect => Transition::Local(&CONNECTING_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::Scoped<Context, Event> for ConnectingScope {
+    type Local = Retries;
+    type Storage = Locals;
+
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn local(&self) -> Retries {
+        Retries { count: 0, limit: 3 }
+    }
+
+    fn slot(storage: &mut Locals) -> Option<&mut Retries> {
+        match storage {
+            Locals::Retries(retries) => Some(retries),
+            _ => None,
+        }
+    }
+
+    fn entry(&self, context: &mut Context, local: &mut Retries) {
+        context.attempts += 1;
+        local.count += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, local: &mut Retries, event: &Event) -> Transition {
+        match event {
+            Event::Fail if local.count == local.limit => Transition::Local(&OFFLINE_STATE, None),
+            Event::Fail => {
+                local.count += 1;
+                Transition::Internal(None)
+            }
+            Event::Succeed => Transition::Local(&ONLINE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context, local: &mut Retries) {
+        context.last_count = local.count;
+    }
+}
+
+impl hsm::State<Context, Event> for OnlineState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Fail => Transition::Local(&CONNECTING_STATE, None),
+            Event::Receive(_) => Transition::Local(&RECEIVING_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl ReceivingScope {
+    fn store(buffer: &mut [u8; 4], event: &Event) {
+        if let Event::Receive(byte) = event {
+            buffer.rotate_left(1);
+            buffer[3] = *byte;
+        }
+    }
+}
+
+impl hsm::Scoped<Context, Event> for ReceivingScope {
+    type Local = [u8; 4];
+    type Storage = Locals;
+
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn local(&self) -> [u8; 4] {
+        [0; 4]
+    }
+
+    fn slot(storage: &mut Locals) -> Option<&mut [u8; 4]> {
+        match storage {
+            Locals::Buffer(buffer) => Some(buffer),
+            _ => None,
+        }
+    }
+
+    fn transition(
+        &self,
+        _context: &mut Context,
+        buffer: &mut [u8; 4],
+        event: &Event,
+    ) -> Transition {
+        match event {
+            Event::Receive(_) => {
+                Self::store(buffer, event);
+                Transition::Internal(None)
+            }
+            Event::Succeed => Transition::Local(&ONLINE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context, buffer: &mut [u8; 4]) {
+        context.flushed += buffer.iter().filter(|byte| **byte != 0).count();
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static OFFLINE_STATE: OfflineState = OfflineState;
+static CONNECTING_STATE: hsm::ScopedState<ConnectingScope> = hsm::ScopedState::new(ConnectingScope);
+static ONLINE_STATE: OnlineState = OnlineState;
+static RECEIVING_STATE: hsm::ScopedState<ReceivingScope> = hsm::ScopedState::new(ReceivingScope);
+
+fn create_context() -> Context {
+    Context {
+        attempts: 0,
+        last_count: 0,
+        flushed: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn local_lifetime() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(machine.local(&CONNECTING_STATE).is_none());
+
+    machine.dispatch(&mut context, &Event::Connect);
+    assert!(core::ptr::eq(machine.active(), &CONNECTING_STATE));
+    assert_eq!(
+        machine
+            .local(&CONNECTING_STATE)
+            .and_then(Locals::retries)
+            .map(|local| local.count),
+        Some(1)
+    );
+
+    machine.dispatch(&mut context, &Event::Fail);
+    assert_eq!(
+        machine
+            .local(&CONNECTING_STATE)
+            .and_then(Locals::retries)
+            .map(|local| local.count),
+        Some(2)
+    );
+
+    machine.dispatch(&mut context, &Event::Succeed);
+    assert!(core::ptr::eq(machine.active(), &ONLINE_STATE));
+    assert!(machine.local(&CONNECTING_STATE).is_none());
+    assert_eq!(context.last_count, 2);
+}
+
+#[test]
+fn multi_reset() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Connect);
+        machine.dispatch(&mut context, &Event::Fail);
+        machine.dispatch(&mut context, &Event::Fail);
+        assert_eq!(
+            machine
+                .local(&CONNECTING_STATE)
+                .and_then(Locals::retries)
+                .map(|local| local.count),
+            Some(3)
+        );
+
+        machine.dispatch(&mut context, &Event::Fail);
+        assert!(core::ptr::eq(machine.active(), &OFFLINE_STATE));
+        assert!(machine.local(&CONNECTING_STATE).is_none());
+        assert_eq!(context.last_count, 3);
+        assert_eq!(context.attempts, i + 1);
+    }
+}
+
+#[test]
+fn local_overflow() {
+    let mut context = create_context();
+    let mut machine: hsm::ScopedStateMachine<Context, Event, Locals, 0> =
+        hsm::StateMachine::new(&ROOT_STATE);
+
+    machine.start(&mut context);
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Connect),
+        Err(hsm::DispatchError::LocalOverflow)
+    );
+    assert!(core::ptr::eq(machine.active(), &OFFLINE_STATE));
+    assert_eq!(context.attempts, 0);
+
+    let mut machine: hsm::ScopedStateMachine<Context, Event, Retries, 1> =
+        hsm::StateMachine::new(&ROOT_STATE);
+
+    machine.start(&mut context);
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Connect),
+        Err(hsm::DispatchError::MissingLocal)
+    );
+    assert!(core::ptr::eq(machine.active(), &OFFLINE_STATE));
+    assert_eq!(context.attempts, 0);
+}
+
+#[test]
+fn mixed_locals() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Connect);
+        machine.dispatch(&mut context, &Event::Succeed);
+        assert!(core::ptr::eq(machine.active(), &ONLINE_STATE));
+
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Receive(1)),
+            Ok(hsm::DispatchOutcome::Transitioned)
+        );
+        assert!(core::ptr::eq(machine.active(), &RECEIVING_STATE));
+        assert_eq!(
+            machine.local(&RECEIVING_STATE).and_then(Locals::buffer),
+            Some(&[0; 4])
+        );
+
+        machine.dispatch(&mut context, &Event::Receive(7));
+        machine.dispatch(&mut context, &Event::Receive(9));
+        assert_eq!(
+            machine.local(&RECEIVING_STATE).and_then(Locals::buffer),
+            Some(&[0, 0, 7, 9])
+        );
+
+        machine.dispatch(&mut context, &Event::Succeed);
+        assert!(machine.local(&RECEIVING_STATE).is_none());
+        assert_eq!(context.flushed, 2 * (i + 1));
+
+        machine.dispatch(&mut context, &Event::Fail);
+        machine.dispatch(&mut context, &Event::Fail);
+        machine.dispatch(&mut context, &Event::Fail);
+        machine.dispatch(&mut context, &Event::Fail);
+        assert!(core::ptr::eq(machine.active(), &OFFLINE_STATE));
+    }
+}
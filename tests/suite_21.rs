@@ -0,0 +1,217 @@
+use hsm::{StateTree, StaticTransition};
+
+struct Context {
+    brightness: u32,
+    entries: usize,
+    exits: usize,
+    actions: usize,
+}
+
+enum Event {
+    Toggle,
+    Boost,
+    Reset,
+    Tick,
+    Orphan,
+    Stray,
+}
+
+type Transition = StaticTransition<LampState, Context, Event>;
+
+type StateMachine = hsm::StaticStateMachine<LampState, Context, Event>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LampState {
+    Root,
+    Off,
+    On,
+    Dim,
+    Bright,
+    Orphan,
+    Stray,
+}
+
+impl LampState {
+    fn reset(context: &mut Context, _event: &Event) {
+        context.brightness = 0;
+    }
+
+    fn action(context: &mut Context, _event: &Event) {
+        context.actions += 1;
+    }
+}
+
+impl StateTree<Context, Event> for LampState {
+    fn parent(self) -> Option<Self> {
+        match self {
+            LampState::Root | LampState::Orphan => None,
+            LampState::Off | LampState::On | LampState::Stray => Some(LampState::Root),
+            LampState::Dim | LampState::Bright => Some(LampState::On),
+        }
+    }
+
+    fn entry(self, context: &mut Context) {
+        context.entries += 1;
+
+        match self {
+            LampState::Dim => context.brightness = 10,
+            LampState::Bright => context.brightness = 100,
+            _ => {}
+        }
+    }
+
+    fn initial(self) -> Option<Self> {
+        match self {
+            LampState::Root => Some(LampState::Off),
+            LampState::On | LampState::Stray => Some(LampState::Dim),
+            _ => None,
+        }
+    }
+
+    fn transition(self, _context: &mut Context, event: &Event) -> Transition {
+        match (self, event) {
+            (LampState::Off, Event::Toggle) => Transition::Local(LampState::On, None),
+            (LampState::On, Event::Toggle) => Transition::Local(LampState::Off, Some(Self::reset)),
+            (LampState::On, Event::Reset) => Transition::External(LampState::On, None),
+            (LampState::Dim, Event::Boost) => Transition::Local(LampState::Bright, None),
+            (LampState::Bright, Event::Tick) => {
+                Transition::Local(LampState::Bright, Some(Self::action))
+            }
+            (LampState::Dim, Event::Tick) => Transition::Internal(Some(Self::action)),
+            (LampState::Root, Event::Orphan) => Transition::Local(LampState::Orphan, None),
+            (LampState::Off, Event::Stray) => Transition::Local(LampState::Stray, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(self, context: &mut Context) {
+        context.exits += 1;
+    }
+}
+
+fn create_context() -> Context {
+    Context {
+        brightness: 0,
+        entries: 0,
+        exits: 0,
+        actions: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(LampState::Root)
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert_eq!(machine.active(), LampState::Root);
+    assert!(!machine.is_started());
+
+    machine.start(&mut context);
+    assert_eq!(machine.active(), LampState::Off);
+    assert_eq!(context.entries, 2);
+}
+
+#[test]
+fn multi_toggle() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Toggle),
+            Ok(hsm::DispatchOutcome::Transitioned)
+        );
+        assert_eq!(machine.active(), LampState::Dim);
+
+        machine.dispatch(&mut context, &Event::Boost);
+        assert_eq!(machine.active(), LampState::Bright);
+        assert_eq!(context.brightness, 100);
+
+        machine.dispatch(&mut context, &Event::Reset);
+        assert_eq!(machine.active(), LampState::Dim);
+        assert_eq!(context.brightness, 10);
+
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert_eq!(machine.active(), LampState::Off);
+        assert_eq!(context.brightness, 0);
+        assert_eq!(context.exits, 6 * (i + 1));
+    }
+}
+
+#[test]
+fn self_transitions() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Toggle);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Tick),
+        Ok(hsm::DispatchOutcome::Handled)
+    );
+    assert_eq!(context.actions, 1);
+
+    machine.dispatch(&mut context, &Event::Boost);
+    let entries = context.entries;
+    let exits = context.exits;
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Tick),
+        Ok(hsm::DispatchOutcome::Handled)
+    );
+    assert_eq!(machine.active(), LampState::Bright);
+    assert_eq!(context.actions, 2);
+    assert_eq!(context.entries, entries);
+    assert_eq!(context.exits, exits);
+}
+
+#[test]
+fn errors() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Toggle),
+        Err(hsm::DispatchError::NotStarted)
+    );
+
+    machine.start(&mut context);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Boost),
+        Err(hsm::DispatchError::UnhandledEvent)
+    );
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Orphan),
+        Err(hsm::DispatchError::DisjointStates)
+    );
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Stray),
+        Err(hsm::DispatchError::InvalidInitialState)
+    );
+    assert_eq!(machine.active(), LampState::Off);
+
+    machine.stop(&mut context);
+    assert!(!machine.is_started());
+    assert_eq!(context.exits, 2);
+}
+
+#[test]
+fn shallow_machine() {
+    let mut context = create_context();
+    let mut machine: hsm::StaticStateMachine<LampState, Context, Event, 1> =
+        hsm::StaticStateMachine::new(LampState::Off);
+
+    assert_eq!(
+        machine.try_start(&mut context),
+        Err(hsm::DispatchError::DepthExceeded)
+    );
+    assert!(!machine.is_started());
+    assert_eq!(context.entries, 0);
+}
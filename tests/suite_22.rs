@@ -0,0 +1,221 @@
+#![cfg(feature = "macros")]
+
+struct Context {
+    brightness: u32,
+    on_entry: usize,
+    on_exit: usize,
+    dim_entry: usize,
+    bright_entry: usize,
+    ticks: usize,
+}
+
+enum Event {
+    Toggle,
+    Boost,
+    Reset,
+    Tick,
+    Level(u32),
+}
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+fn on_entry(context: &mut Context) {
+    context.on_entry += 1;
+}
+
+fn on_exit(context: &mut Context) {
+    context.on_exit += 1;
+}
+
+fn dim_entry(context: &mut Context) {
+    context.dim_entry += 1;
+    context.brightness = 10;
+}
+
+fn bright_entry(context: &mut Context) {
+    context.bright_entry += 1;
+    context.brightness = 100;
+}
+
+fn reset(context: &mut Context, _event: &Event) {
+    context.brightness = 0;
+}
+
+fn tick(context: &mut Context, _event: &Event) {
+    context.ticks += 1;
+}
+
+fn level(context: &mut Context, event: &Event) {
+    if let Event::Level(value) = event {
+        context.brightness = *value;
+    }
+}
+
+hsm::hsm! {
+    context = Context;
+    event = Event;
+    depth = 3;
+    machine = StateMachine;
+
+    ROOT_STATE {
+        id = 1;
+        initial = OFF_STATE;
+
+        on Event::Tick / tick;
+
+        OFF_STATE {
+            on Event::Toggle => history ON_STATE;
+            on Event::Reset => ON_STATE;
+        }
+
+        ON_STATE {
+            entry = on_entry;
+            exit = on_exit;
+            initial = DIM_STATE;
+            history = shallow;
+
+            on Event::Toggle => OFF_STATE / reset;
+            on Event::Reset => external ON_STATE;
+            on Event::Level(_) / level;
+
+            DIM_STATE {
+                entry = dim_entry;
+
+                on Event::Boost => BRIGHT_STATE;
+            }
+
+            BRIGHT_STATE {
+                entry = bright_entry;
+
+                on Event::Boost | Event::Tick => DIM_STATE;
+            }
+        }
+    }
+}
+
+fn create_context() -> Context {
+    Context {
+        brightness: 0,
+        on_entry: 0,
+        on_exit: 0,
+        dim_entry: 0,
+        bright_entry: 0,
+        ticks: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &OFF_STATE));
+
+    machine.dispatch(&mut context, &Event::Toggle);
+    assert!(core::ptr::eq(machine.active(), &DIM_STATE));
+    assert_eq!(context.on_entry, 1);
+    assert_eq!(context.dim_entry, 1);
+}
+
+#[test]
+fn identity() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert_eq!(machine.active().name(), "ROOT_STATE");
+    assert_eq!(machine.active().id(), Some(1));
+
+    machine.start(&mut context);
+    assert_eq!(machine.active().name(), "OFF_STATE");
+    assert!(machine.active() == &OFF_STATE);
+    assert!(machine.active() != &ON_STATE);
+}
+
+#[test]
+fn multi_toggle() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert!(core::ptr::eq(machine.active(), &DIM_STATE));
+        assert_eq!(context.brightness, 10);
+
+        machine.dispatch(&mut context, &Event::Boost);
+        assert!(core::ptr::eq(machine.active(), &BRIGHT_STATE));
+        assert_eq!(context.brightness, 100);
+
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert!(core::ptr::eq(machine.active(), &OFF_STATE));
+        assert_eq!(context.brightness, 0);
+        assert_eq!(context.on_exit, 4 * i + 1);
+
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert!(core::ptr::eq(machine.active(), &BRIGHT_STATE));
+        assert_eq!(context.bright_entry, 3 * i + 2);
+
+        machine.dispatch(&mut context, &Event::Tick);
+        assert!(core::ptr::eq(machine.active(), &DIM_STATE));
+
+        machine.dispatch(&mut context, &Event::Boost);
+        machine.dispatch(&mut context, &Event::Reset);
+        assert!(core::ptr::eq(machine.active(), &DIM_STATE));
+        assert_eq!(context.on_entry, 4 * i + 3);
+
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert!(core::ptr::eq(machine.active(), &OFF_STATE));
+
+        machine.dispatch(&mut context, &Event::Reset);
+        assert!(core::ptr::eq(machine.active(), &DIM_STATE));
+        assert_eq!(context.dim_entry, 4 * i + 4);
+
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert!(core::ptr::eq(machine.active(), &OFF_STATE));
+    }
+}
+
+#[test]
+fn internal() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Tick),
+        Ok(hsm::DispatchOutcome::Handled)
+    );
+    assert_eq!(context.ticks, 1);
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Level(50)),
+        Err(hsm::DispatchError::UnhandledEvent)
+    );
+
+    machine.dispatch(&mut context, &Event::Toggle);
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Level(50)),
+        Ok(hsm::DispatchOutcome::Handled)
+    );
+    assert!(core::ptr::eq(machine.active(), &DIM_STATE));
+    assert_eq!(context.brightness, 50);
+}
+
+#[test]
+fn depth() {
+    assert_eq!(RootState::DEPTH, 3);
+    assert!(StateMachine::validate(&[
+        &ROOT_STATE,
+        &OFF_STATE,
+        &ON_STATE,
+        &DIM_STATE,
+        &BRIGHT_STATE
+    ])
+    .is_valid());
+}
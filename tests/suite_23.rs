@@ -0,0 +1,239 @@
+#![cfg(feature = "macros")]
+
+struct Context {
+    brightness: u32,
+    on_entry: usize,
+    on_exit: usize,
+    toggles: usize,
+}
+
+enum Event {
+    Toggle,
+    Boost,
+    Level(u32),
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+#[derive(hsm::State)]
+#[state(name = "Root", id = 1, behavior, initial = OFF_STATE, context = Context, event = Event)]
+struct RootState;
+
+#[derive(hsm::State)]
+#[state(parent = ROOT_STATE, behavior, context = Context, event = Event)]
+struct OffState;
+
+#[derive(hsm::State)]
+#[state(parent = ROOT_STATE, behavior, initial = DIM_STATE, context = Context, event = Event)]
+struct OnState;
+
+#[derive(hsm::State)]
+#[state(parent = ON_STATE, behavior, context = Context, event = Event)]
+struct LevelState(u32);
+
+#[derive(hsm::State)]
+#[state(parent = ROOT_STATE, context = Context, event = Event)]
+struct IdleState;
+
+#[derive(hsm::State)]
+#[state(parent = ROOT_STATE, behavior, context = Context, event = Event)]
+struct PresetState<T: 'static>(T);
+
+#[hsm::behavior]
+impl RootState {
+    #[on(Event::Level(_))]
+    fn level(context: &mut Context, event: &Event) -> Transition {
+        if let Event::Level(value) = event {
+            context.brightness = *value;
+        }
+
+        Transition::Internal(None)
+    }
+}
+
+#[hsm::behavior]
+impl OffState {
+    #[on(Event::Toggle)]
+    fn toggle(_context: &mut Context, _event: &Event) -> Transition {
+        Transition::Local(&ON_STATE, Some(OnState::count))
+    }
+}
+
+impl OnState {
+    fn count(context: &mut Context, _event: &Event) {
+        context.toggles += 1;
+    }
+}
+
+#[hsm::behavior]
+impl OnState {
+    #[entry]
+    fn light(context: &mut Context) {
+        context.on_entry += 1;
+    }
+
+    #[on(Event::Toggle)]
+    fn toggle(_context: &mut Context, _event: &Event) -> Transition {
+        Transition::Local(&OFF_STATE, Some(Self::count))
+    }
+
+    #[exit]
+    fn darken(context: &mut Context) {
+        context.on_exit += 1;
+        context.brightness = 0;
+    }
+}
+
+#[hsm::behavior]
+impl LevelState {
+    #[entry]
+    fn entry(&self, context: &mut Context) {
+        context.brightness = self.0;
+    }
+
+    #[on(Event::Boost)]
+    fn boost(&self, _context: &mut Context, _event: &Event) -> Transition {
+        if core::ptr::eq(self, &DIM_STATE) {
+            Transition::Local(&BRIGHT_STATE, None)
+        } else {
+            Transition::Local(&DIM_STATE, None)
+        }
+    }
+}
+
+#[hsm::behavior]
+impl PresetState<u32> {
+    #[entry]
+    fn enter(&self, context: &mut Context) {
+        context.brightness = self.0;
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static OFF_STATE: OffState = OffState;
+static ON_STATE: OnState = OnState;
+static DIM_STATE: LevelState = LevelState(10);
+static BRIGHT_STATE: LevelState = LevelState(100);
+static IDLE_STATE: IdleState = IdleState;
+static PRESET_STATE: PresetState<u32> = PresetState(42);
+
+fn create_context() -> Context {
+    Context {
+        brightness: 0,
+        on_entry: 0,
+        on_exit: 0,
+        toggles: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &OFF_STATE));
+
+    machine.dispatch(&mut context, &Event::Toggle);
+    assert!(core::ptr::eq(machine.active(), &DIM_STATE));
+    assert_eq!(context.on_entry, 1);
+    assert_eq!(context.brightness, 10);
+}
+
+#[test]
+fn identity() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert_eq!(machine.active().name(), "Root");
+    assert_eq!(machine.active().id(), Some(1));
+
+    machine.start(&mut context);
+    assert_eq!(machine.active().name(), "OffState");
+    assert!(machine.active() == &OFF_STATE);
+}
+
+#[test]
+fn multi_toggle() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert!(core::ptr::eq(machine.active(), &DIM_STATE));
+        assert_eq!(context.brightness, 10);
+
+        machine.dispatch(&mut context, &Event::Boost);
+        assert!(core::ptr::eq(machine.active(), &BRIGHT_STATE));
+        assert_eq!(context.brightness, 100);
+
+        machine.dispatch(&mut context, &Event::Level(50));
+        assert!(core::ptr::eq(machine.active(), &BRIGHT_STATE));
+        assert_eq!(context.brightness, 50);
+
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert!(core::ptr::eq(machine.active(), &OFF_STATE));
+        assert_eq!(context.brightness, 0);
+        assert_eq!(context.on_entry, i + 1);
+        assert_eq!(context.on_exit, i + 1);
+        assert_eq!(context.toggles, 2 * i + 2);
+    }
+}
+
+#[test]
+fn unhandled() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Boost),
+        Err(hsm::DispatchError::UnhandledEvent)
+    );
+    assert!(core::ptr::eq(machine.active(), &OFF_STATE));
+}
+
+#[test]
+fn bare_derive() {
+    let mut context = create_context();
+    let mut machine = StateMachine::new(&IDLE_STATE);
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(machine.active().name(), "IdleState");
+
+    for i in 0..1000 {
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Toggle),
+            Err(hsm::DispatchError::UnhandledEvent)
+        );
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Level(i)),
+            Ok(hsm::DispatchOutcome::Handled)
+        );
+        assert_eq!(context.brightness, i);
+    }
+}
+
+#[test]
+fn generic_derive() {
+    let mut context = create_context();
+
+    for _ in 0..1000 {
+        let mut machine = StateMachine::new(&PRESET_STATE);
+        context.brightness = 0;
+
+        machine.start(&mut context);
+        assert!(core::ptr::eq(machine.active(), &PRESET_STATE));
+        assert_eq!(context.brightness, 42);
+    }
+}
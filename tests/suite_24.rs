@@ -0,0 +1,119 @@
+use hsm::ValidationIssue;
+
+struct Context;
+
+enum Event {}
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+type States = [&'static dyn hsm::State<Context, Event>];
+
+struct LevelState(usize);
+struct CycleState(usize);
+struct OrphanState;
+struct StrayState;
+
+impl hsm::State<Context, Event> for LevelState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        match self.0 {
+            0 => None,
+            level => Some(&LEVEL_STATES[level - 1]),
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for CycleState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        match self.0 {
+            0 => Some(&CYCLE_STATES[1]),
+            _ => Some(&CYCLE_STATES[0]),
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for OrphanState {}
+
+impl hsm::State<Context, Event> for StrayState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ORPHAN_STATE)
+    }
+}
+
+static LEVEL_STATES: [LevelState; 10] = [
+    LevelState(0),
+    LevelState(1),
+    LevelState(2),
+    LevelState(3),
+    LevelState(4),
+    LevelState(5),
+    LevelState(6),
+    LevelState(7),
+    LevelState(8),
+    LevelState(9),
+];
+static CYCLE_STATES: [CycleState; 2] = [CycleState(0), CycleState(1)];
+static ORPHAN_STATE: OrphanState = OrphanState;
+static STRAY_STATE: StrayState = StrayState;
+
+#[test]
+fn valid() {
+    let states: &States = &[&LEVEL_STATES[0], &LEVEL_STATES[1], &LEVEL_STATES[2]];
+
+    let report = StateMachine::validate(states);
+    assert!(report.is_valid());
+    assert!(core::ptr::eq(report.root().unwrap(), &LEVEL_STATES[0]));
+    assert_eq!(report.issues().count(), 0);
+}
+
+#[test]
+fn cycles() {
+    let states: &States = &[&LEVEL_STATES[0], &CYCLE_STATES[0], &CYCLE_STATES[1]];
+
+    let report = StateMachine::validate(states);
+    assert!(!report.is_valid());
+    assert!(report
+        .issues()
+        .eq([ValidationIssue::Cycle(1), ValidationIssue::Cycle(2)]));
+
+    let states: &States = &[&CYCLE_STATES[0]];
+
+    let report = StateMachine::validate(states);
+    assert!(report.root().is_none());
+    assert!(report.issues().eq([ValidationIssue::Cycle(0)]));
+}
+
+#[test]
+fn depth() {
+    let states: &States = &[&LEVEL_STATES[1], &LEVEL_STATES[7], &LEVEL_STATES[8]];
+
+    let report = StateMachine::validate(states);
+    assert!(core::ptr::eq(report.root().unwrap(), &LEVEL_STATES[0]));
+    assert!(report.issues().eq([ValidationIssue::DepthExceeded(2)]));
+
+    let report = hsm::StateMachine::<Context, Event, 16>::validate(states);
+    assert!(report.is_valid());
+}
+
+#[test]
+fn separate_trees() {
+    let states: &States = &[
+        &LEVEL_STATES[0],
+        &LEVEL_STATES[1],
+        &ORPHAN_STATE,
+        &STRAY_STATE,
+    ];
+
+    let report = StateMachine::validate(states);
+    assert!(report.issues().eq([
+        ValidationIssue::MultipleRoots(2),
+        ValidationIssue::Unreachable(3),
+    ]));
+    assert_eq!(
+        format!("{:?}", report),
+        "[MultipleRoots(2), Unreachable(3)]"
+    );
+    assert_eq!(
+        ValidationIssue::Unreachable(3).to_string(),
+        "State #3 is not reachable from the root state!"
+    );
+}
@@ -0,0 +1,173 @@
+use hsm::State;
+
+struct Context {
+    toggles: usize,
+}
+
+enum Event {
+    Toggle,
+    Finish,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+struct RootState;
+struct OffState;
+struct OnState;
+struct LevelState(u32);
+
+impl hsm::State<Context, Event> for RootState {
+    fn name(&self) -> &'static str {
+        "Root"
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&OFF_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for OffState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Toggle => Transition::Local(&ON_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl OnState {
+    fn toggle(context: &mut Context, _event: &Event) {
+        context.toggles += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for OnState {
+    fn name(&self) -> &'static str {
+        "On"
+    }
+
+    fn id(&self) -> Option<u32> {
+        Some(42)
+    }
+
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&DIM_STATE, None))
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Toggle => Transition::Local(&OFF_STATE, Some(Self::toggle)),
+            Event::Finish => Transition::Local(&ON_FINAL_STATE, None),
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for LevelState {
+    fn name(&self) -> &'static str {
+        match self.0 {
+            0..=50 => "Dim",
+            _ => "Bright",
+        }
+    }
+
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ON_STATE)
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static OFF_STATE: OffState = OffState;
+static ON_STATE: OnState = OnState;
+static DIM_STATE: LevelState = LevelState(10);
+static BRIGHT_STATE: LevelState = LevelState(100);
+static ON_FINAL_STATE: hsm::FinalState<Context, Event> = hsm::FinalState::new(&ON_STATE);
+static ROOT_FINAL_STATE: hsm::FinalState<Context, Event> = hsm::FinalState::new(&ROOT_STATE);
+
+fn create_context() -> Context {
+    Context { toggles: 0 }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn names() {
+    assert_eq!(ROOT_STATE.name(), "Root");
+    assert!(OFF_STATE.name().ends_with("OffState"));
+    assert_eq!(DIM_STATE.name(), "Dim");
+    assert_eq!(BRIGHT_STATE.name(), "Bright");
+}
+
+#[test]
+fn ids() {
+    assert_eq!(ON_STATE.id(), Some(42));
+    assert_eq!(ROOT_STATE.id(), None);
+    assert_eq!(DIM_STATE.id(), None);
+    assert_eq!(ON_FINAL_STATE.id(), None);
+    assert_eq!(ROOT_FINAL_STATE.id(), None);
+}
+
+#[test]
+fn handles() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(machine.active() == &ROOT_STATE);
+
+    machine.start(&mut context);
+    assert!(machine.active() == &OFF_STATE);
+    assert!(machine.active() != &ON_STATE);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert_eq!(machine.active(), &DIM_STATE as &dyn hsm::State<_, _>);
+        assert!(machine.active() != &BRIGHT_STATE);
+
+        machine.dispatch(&mut context, &Event::Toggle);
+        assert!(machine.active() == &OFF_STATE);
+        assert_eq!(context.toggles, i + 1);
+    }
+
+    machine.dispatch(&mut context, &Event::Toggle);
+    assert_eq!(format!("{:?}", machine.active()), "State { name: \"Dim\" }");
+}
+
+#[test]
+fn unit_handles() {
+    let root_state: &dyn hsm::State<Context, Event> = &ROOT_STATE;
+    let off_state: &dyn hsm::State<Context, Event> = &OFF_STATE;
+
+    for _ in 0..1000 {
+        assert!(root_state == &ROOT_STATE);
+        assert!(root_state != &OFF_STATE);
+        assert!(off_state == &OFF_STATE);
+        assert!(off_state != &ROOT_STATE);
+        assert!(root_state != off_state);
+    }
+}
+
+#[test]
+fn final_handles() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Toggle);
+
+    machine.dispatch(&mut context, &Event::Finish);
+    assert!(machine.active() == &ON_FINAL_STATE);
+    assert!(machine.active() != &ROOT_FINAL_STATE);
+    assert!(core::ptr::eq(machine.active(), &ON_FINAL_STATE));
+}
@@ -0,0 +1,230 @@
+struct Context {
+    connects: usize,
+}
+
+enum Event {
+    Connect,
+    Disconnect,
+    Open,
+    Close,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+type StateRef = &'static dyn hsm::State<Context, Event>;
+
+type RegionRef = &'static (dyn hsm::State<Context, Event> + Sync);
+
+struct RootState;
+struct DisconnectedState;
+struct ConnectedState;
+struct LinkRegion;
+struct LinkUpState;
+struct SessionRegion;
+struct SessionIdleState;
+struct SessionOpenState;
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&DISCONNECTED_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for DisconnectedState {
+    fn parent(&self) -> Option<StateRef> {
+        Some(&ROOT_STATE)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Connect => Transition::Local(&CONNECTED_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for ConnectedState {
+    fn parent(&self) -> Option<StateRef> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.connects += 1;
+    }
+
+    fn regions(&self) -> &'static [RegionRef] {
+        &CONNECTED_REGIONS
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Disconnect => Transition::Local(&DISCONNECTED_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for LinkRegion {
+    fn parent(&self) -> Option<StateRef> {
+        Some(&CONNECTED_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&LINK_UP_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for LinkUpState {
+    fn parent(&self) -> Option<StateRef> {
+        Some(&LINK_REGION)
+    }
+}
+
+impl hsm::State<Context, Event> for SessionRegion {
+    fn parent(&self) -> Option<StateRef> {
+        Some(&CONNECTED_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&SESSION_IDLE_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for SessionIdleState {
+    fn parent(&self) -> Option<StateRef> {
+        Some(&SESSION_REGION)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Open => Transition::Local(&SESSION_OPEN_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for SessionOpenState {
+    fn parent(&self) -> Option<StateRef> {
+        Some(&SESSION_REGION)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Close => Transition::Local(&SESSION_IDLE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static DISCONNECTED_STATE: DisconnectedState = DisconnectedState;
+static CONNECTED_STATE: ConnectedState = ConnectedState;
+static LINK_REGION: LinkRegion = LinkRegion;
+static LINK_UP_STATE: LinkUpState = LinkUpState;
+static SESSION_REGION: SessionRegion = SessionRegion;
+static SESSION_IDLE_STATE: SessionIdleState = SessionIdleState;
+static SESSION_OPEN_STATE: SessionOpenState = SessionOpenState;
+
+static CONNECTED_REGIONS: [RegionRef; 2] = [&LINK_REGION, &SESSION_REGION];
+
+fn create_context() -> Context {
+    Context { connects: 0 }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+fn assert_path(machine: &StateMachine, states: &[StateRef]) {
+    assert_eq!(machine.depth(), states.len());
+    assert_eq!(machine.active_path().count(), states.len());
+
+    for (active_state, state) in machine.active_path().zip(states) {
+        assert!(core::ptr::eq(active_state, *state));
+    }
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(!machine.is_in(&ROOT_STATE));
+    assert_path(&machine, &[]);
+
+    machine.start(&mut context);
+    assert!(machine.is_in(&ROOT_STATE));
+    assert!(machine.is_in(&DISCONNECTED_STATE));
+    assert!(!machine.is_in(&CONNECTED_STATE));
+    assert_path(&machine, &[&DISCONNECTED_STATE, &ROOT_STATE]);
+
+    machine.stop(&mut context);
+    assert!(!machine.is_in(&ROOT_STATE));
+    assert_path(&machine, &[]);
+}
+
+#[test]
+fn multi_region() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Connect);
+        assert!(machine.is_in(&CONNECTED_STATE));
+        assert!(machine.is_in(&LINK_UP_STATE));
+        assert!(machine.is_in(&SESSION_IDLE_STATE));
+        assert!(!machine.is_in(&SESSION_OPEN_STATE));
+        assert!(!machine.is_in(&DISCONNECTED_STATE));
+        assert_path(
+            &machine,
+            &[&LINK_UP_STATE, &LINK_REGION, &CONNECTED_STATE, &ROOT_STATE],
+        );
+        assert_eq!(context.connects, i + 1);
+
+        machine.dispatch(&mut context, &Event::Open);
+        assert!(machine.is_in(&SESSION_OPEN_STATE));
+        assert!(machine.is_in(&SESSION_REGION));
+        assert!(!machine.is_in(&SESSION_IDLE_STATE));
+
+        machine.dispatch(&mut context, &Event::Close);
+        assert!(machine.is_in(&SESSION_IDLE_STATE));
+
+        machine.dispatch(&mut context, &Event::Disconnect);
+        assert!(!machine.is_in(&CONNECTED_STATE));
+        assert!(!machine.is_in(&LINK_REGION));
+        assert_path(&machine, &[&DISCONNECTED_STATE, &ROOT_STATE]);
+    }
+}
+
+#[test]
+fn relations() {
+    let root_state: StateRef = &ROOT_STATE;
+    let connected_state: StateRef = &CONNECTED_STATE;
+    let open_state: StateRef = &SESSION_OPEN_STATE;
+
+    assert_eq!(root_state.depth(), 1);
+    assert_eq!(connected_state.depth(), 2);
+    assert_eq!(open_state.depth(), 4);
+
+    assert_eq!(root_state.ancestors().count(), 0);
+    assert!(open_state
+        .ancestors()
+        .zip([&SESSION_REGION as StateRef, &CONNECTED_STATE, &ROOT_STATE])
+        .all(|(ancestor_state, state)| core::ptr::eq(ancestor_state, state)));
+
+    assert!(open_state.is_descendant_of(&CONNECTED_STATE));
+    assert!(open_state.is_descendant_of(&ROOT_STATE));
+    assert!(!open_state.is_descendant_of(&SESSION_OPEN_STATE));
+    assert!(!open_state.is_descendant_of(&LINK_REGION));
+    assert!(!connected_state.is_descendant_of(&SESSION_REGION));
+
+    assert!(connected_state.is_ancestor_of(&SESSION_OPEN_STATE));
+    assert!(connected_state.is_ancestor_of(&LINK_UP_STATE));
+    assert!(!connected_state.is_ancestor_of(&CONNECTED_STATE));
+    assert!(!connected_state.is_ancestor_of(&DISCONNECTED_STATE));
+}
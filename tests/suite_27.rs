@@ -0,0 +1,7 @@
+#![cfg(feature = "macros")]
+
+#[test]
+fn diagnostics() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
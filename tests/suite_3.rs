@@ -1,5 +1,3 @@
-use hsm;
-
 struct Context {
     first_entry: usize,
     second_entry: usize,
@@ -16,35 +14,29 @@ struct Context {
 }
 
 enum Event {
-    Initial,
     Jump,
     Down,
 }
 
 type Transition = hsm::Transition<Context, Event>;
 
+type Initial = hsm::Initial<Context, Event>;
+
 type StateMachine = hsm::StateMachine<Context, Event>;
 
 struct RootState;
-struct InitialState;
 struct FirstState;
 struct SecondState;
 struct ThirdState;
 struct FourthState;
 
 impl hsm::State<Context, Event> for RootState {
-    fn transition(&self, _context: &mut Context, _event: &Event) -> Transition {
-        Transition::Internal(None)
-    }
-}
-
-impl hsm::State<Context, Event> for InitialState {
-    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
-        Some(&ROOT_STATE)
+    fn initial(&self) -> Initial {
+        Some((&FIRST_STATE, None))
     }
 
     fn transition(&self, _context: &mut Context, _event: &Event) -> Transition {
-        Transition::Local(&FIRST_STATE, None)
+        Transition::Internal(None)
     }
 }
 
@@ -157,19 +149,13 @@ impl hsm::State<Context, Event> for FourthState {
 }
 
 static ROOT_STATE: RootState = RootState;
-static INITIAL_STATE: InitialState = InitialState;
 static FIRST_STATE: FirstState = FirstState;
 static SECOND_STATE: SecondState = SecondState;
 static THIRD_STATE: ThirdState = ThirdState;
 static FOURTH_STATE: FourthState = FourthState;
 
 fn create_machine() -> StateMachine {
-    StateMachine::new(&INITIAL_STATE)
-}
-
-fn initial_step(machine: &mut StateMachine, context: &mut Context) {
-    let initial_event = Event::Initial;
-    machine.dispatch(context, &initial_event);
+    StateMachine::new(&ROOT_STATE)
 }
 
 #[test]
@@ -189,9 +175,9 @@ fn startup() {
         fourth_exit: 0,
     };
     let mut machine = create_machine();
-    assert!(core::ptr::eq(machine.active(), &INITIAL_STATE));
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
 
-    initial_step(&mut machine, &mut context);
+    machine.start(&mut context);
     assert!(core::ptr::eq(machine.active(), &FIRST_STATE));
 }
 
@@ -222,9 +208,9 @@ fn multi_jump() {
         fourth_exit: 0,
     };
     let mut machine = create_machine();
-    assert!(core::ptr::eq(machine.active(), &INITIAL_STATE));
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
 
-    initial_step(&mut machine, &mut context);
+    machine.start(&mut context);
     assert!(core::ptr::eq(machine.active(), &FIRST_STATE));
     assert_eq!(context.first_entry, 1);
 
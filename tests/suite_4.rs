@@ -0,0 +1,300 @@
+struct Context {
+    root_entry: usize,
+    outer_entry: usize,
+    middle_entry: usize,
+    inner_entry: usize,
+    other_entry: usize,
+    initial_action: usize,
+    outer_exit: usize,
+    middle_exit: usize,
+    inner_exit: usize,
+    other_exit: usize,
+    root_exit: usize,
+}
+
+enum Event {
+    Leave,
+    Back,
+    Reset,
+    Broken,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+struct RootState;
+struct OuterState;
+struct MiddleState;
+struct InnerState;
+struct OtherState;
+struct BrokenState;
+
+impl hsm::State<Context, Event> for RootState {
+    fn entry(&self, context: &mut Context) {
+        context.root_entry += 1;
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&OUTER_STATE, None))
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.root_exit += 1;
+    }
+}
+
+impl OuterState {
+    fn action(context: &mut Context) {
+        context.initial_action += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for OuterState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.outer_entry += 1;
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&MIDDLE_STATE, Some(Self::action)))
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Leave => Transition::Local(&OTHER_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.outer_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for MiddleState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&OUTER_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.middle_entry += 1;
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&INNER_STATE, None))
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.middle_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for InnerState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&MIDDLE_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.inner_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Reset => Transition::External(&OUTER_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.inner_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for OtherState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.other_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Back => Transition::Local(&OUTER_STATE, None),
+            Event::Broken => Transition::Local(&BROKEN_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.other_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for BrokenState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&INNER_STATE, None))
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static OUTER_STATE: OuterState = OuterState;
+static MIDDLE_STATE: MiddleState = MiddleState;
+static INNER_STATE: InnerState = InnerState;
+static OTHER_STATE: OtherState = OtherState;
+static BROKEN_STATE: BrokenState = BrokenState;
+
+fn create_context() -> Context {
+    Context {
+        root_entry: 0,
+        outer_entry: 0,
+        middle_entry: 0,
+        inner_entry: 0,
+        other_entry: 0,
+        initial_action: 0,
+        outer_exit: 0,
+        middle_exit: 0,
+        inner_exit: 0,
+        other_exit: 0,
+        root_exit: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &INNER_STATE));
+    assert_eq!(context.root_entry, 1);
+    assert_eq!(context.outer_entry, 1);
+    assert_eq!(context.initial_action, 1);
+    assert_eq!(context.middle_entry, 1);
+    assert_eq!(context.inner_entry, 1);
+}
+
+fn leave_step(machine: &mut StateMachine, context: &mut Context) {
+    let leave_event = Event::Leave;
+    machine.dispatch(context, &leave_event);
+}
+
+fn back_step(machine: &mut StateMachine, context: &mut Context) {
+    let back_event = Event::Back;
+    machine.dispatch(context, &back_event);
+}
+
+fn reset_step(machine: &mut StateMachine, context: &mut Context) {
+    let reset_event = Event::Reset;
+    machine.dispatch(context, &reset_event);
+}
+
+fn broken_step(machine: &mut StateMachine, context: &mut Context) {
+    let broken_event = Event::Broken;
+    machine.dispatch(context, &broken_event);
+}
+
+#[test]
+fn multi_drill() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &INNER_STATE));
+
+    for i in 0..1000 {
+        leave_step(&mut machine, &mut context);
+        assert!(core::ptr::eq(machine.active(), &OTHER_STATE));
+        assert_eq!(context.inner_exit, 2 * i + 1);
+        assert_eq!(context.middle_exit, 2 * i + 1);
+        assert_eq!(context.outer_exit, 2 * i + 1);
+        assert_eq!(context.other_entry, i + 1);
+
+        back_step(&mut machine, &mut context);
+        assert!(core::ptr::eq(machine.active(), &INNER_STATE));
+        assert_eq!(context.other_exit, i + 1);
+        assert_eq!(context.outer_entry, 2 * i + 2);
+        assert_eq!(context.initial_action, 2 * i + 2);
+        assert_eq!(context.middle_entry, 2 * i + 2);
+        assert_eq!(context.inner_entry, 2 * i + 2);
+
+        reset_step(&mut machine, &mut context);
+        assert!(core::ptr::eq(machine.active(), &INNER_STATE));
+        assert_eq!(context.inner_exit, 2 * i + 2);
+        assert_eq!(context.middle_exit, 2 * i + 2);
+        assert_eq!(context.outer_exit, 2 * i + 2);
+        assert_eq!(context.outer_entry, 2 * i + 3);
+        assert_eq!(context.initial_action, 2 * i + 3);
+        assert_eq!(context.middle_entry, 2 * i + 3);
+        assert_eq!(context.inner_entry, 2 * i + 3);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Initial state is not a child of its composite state!")]
+fn broken_initial() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &INNER_STATE));
+
+    leave_step(&mut machine, &mut context);
+    assert!(core::ptr::eq(machine.active(), &OTHER_STATE));
+
+    broken_step(&mut machine, &mut context);
+}
+
+#[test]
+fn multi_restart() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+    assert!(!machine.is_started());
+
+    for i in 0..1000 {
+        machine.start(&mut context);
+        assert!(machine.is_started());
+        assert!(core::ptr::eq(machine.active(), &INNER_STATE));
+        assert_eq!(context.root_entry, i + 1);
+        assert_eq!(context.outer_entry, i + 1);
+        assert_eq!(context.middle_entry, i + 1);
+        assert_eq!(context.inner_entry, i + 1);
+
+        machine.stop(&mut context);
+        assert!(!machine.is_started());
+        assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+        assert_eq!(context.inner_exit, i + 1);
+        assert_eq!(context.middle_exit, i + 1);
+        assert_eq!(context.outer_exit, i + 1);
+        assert_eq!(context.root_exit, i + 1);
+    }
+}
+
+#[test]
+#[should_panic(expected = "State machine has not been started!")]
+fn not_started() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    leave_step(&mut machine, &mut context);
+}
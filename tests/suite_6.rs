@@ -0,0 +1,327 @@
+struct Context {
+    idle_entry: usize,
+    running_entry: usize,
+    slow_entry: usize,
+    fast_entry: usize,
+    cruise_entry: usize,
+    boost_entry: usize,
+    fault_entry: usize,
+    running_exit: usize,
+    fast_exit: usize,
+    boost_exit: usize,
+    fault_exit: usize,
+}
+
+enum Event {
+    Start,
+    Speed,
+    Boost,
+    Fail,
+    Resume,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+struct RootState;
+struct OperatingState;
+struct IdleState;
+struct RunningState;
+struct SlowState;
+struct FastState;
+struct CruiseState;
+struct BoostState;
+struct FaultState;
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&OPERATING_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for OperatingState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&IDLE_STATE, None))
+    }
+
+    fn history(&self) -> Option<hsm::HistoryKind> {
+        Some(hsm::HistoryKind::Deep)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Fail => Transition::Local(&FAULT_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for IdleState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&OPERATING_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.idle_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Start => Transition::Local(&RUNNING_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for RunningState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&OPERATING_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.running_entry += 1;
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&SLOW_STATE, None))
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.running_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for SlowState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&RUNNING_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.slow_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Speed => Transition::Local(&FAST_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for FastState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&RUNNING_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.fast_entry += 1;
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&CRUISE_STATE, None))
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.fast_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for CruiseState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&FAST_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.cruise_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Boost => Transition::Local(&BOOST_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for BoostState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&FAST_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.boost_entry += 1;
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.boost_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for FaultState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.fault_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Resume => Transition::History(&OPERATING_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.fault_exit += 1;
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static OPERATING_STATE: OperatingState = OperatingState;
+static IDLE_STATE: IdleState = IdleState;
+static RUNNING_STATE: RunningState = RunningState;
+static SLOW_STATE: SlowState = SlowState;
+static FAST_STATE: FastState = FastState;
+static CRUISE_STATE: CruiseState = CruiseState;
+static BOOST_STATE: BoostState = BoostState;
+static FAULT_STATE: FaultState = FaultState;
+
+fn create_context() -> Context {
+    Context {
+        idle_entry: 0,
+        running_entry: 0,
+        slow_entry: 0,
+        fast_entry: 0,
+        cruise_entry: 0,
+        boost_entry: 0,
+        fault_entry: 0,
+        running_exit: 0,
+        fast_exit: 0,
+        boost_exit: 0,
+        fault_exit: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+}
+
+fn start_step(machine: &mut StateMachine, context: &mut Context) {
+    let start_event = Event::Start;
+    machine.dispatch(context, &start_event);
+}
+
+fn speed_step(machine: &mut StateMachine, context: &mut Context) {
+    let speed_event = Event::Speed;
+    machine.dispatch(context, &speed_event);
+}
+
+fn boost_step(machine: &mut StateMachine, context: &mut Context) {
+    let boost_event = Event::Boost;
+    machine.dispatch(context, &boost_event);
+}
+
+fn fail_step(machine: &mut StateMachine, context: &mut Context) {
+    let fail_event = Event::Fail;
+    machine.dispatch(context, &fail_event);
+}
+
+fn resume_step(machine: &mut StateMachine, context: &mut Context) {
+    let resume_event = Event::Resume;
+    machine.dispatch(context, &resume_event);
+}
+
+#[test]
+fn empty_history() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+
+    fail_step(&mut machine, &mut context);
+    assert!(core::ptr::eq(machine.active(), &FAULT_STATE));
+
+    resume_step(&mut machine, &mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(context.idle_entry, 2);
+}
+
+#[test]
+fn partial_history() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+
+    start_step(&mut machine, &mut context);
+    speed_step(&mut machine, &mut context);
+    assert!(core::ptr::eq(machine.active(), &CRUISE_STATE));
+
+    fail_step(&mut machine, &mut context);
+    assert!(core::ptr::eq(machine.active(), &FAULT_STATE));
+
+    resume_step(&mut machine, &mut context);
+    assert!(core::ptr::eq(machine.active(), &CRUISE_STATE));
+    assert_eq!(context.running_entry, 2);
+    assert_eq!(context.fast_entry, 2);
+    assert_eq!(context.cruise_entry, 2);
+    assert_eq!(context.slow_entry, 1);
+}
+
+#[test]
+fn multi_resume() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+
+    start_step(&mut machine, &mut context);
+    speed_step(&mut machine, &mut context);
+    boost_step(&mut machine, &mut context);
+    assert!(core::ptr::eq(machine.active(), &BOOST_STATE));
+    assert_eq!(context.boost_entry, 1);
+
+    for i in 0..1000 {
+        fail_step(&mut machine, &mut context);
+        assert!(core::ptr::eq(machine.active(), &FAULT_STATE));
+        assert_eq!(context.boost_exit, i + 1);
+        assert_eq!(context.fast_exit, i + 1);
+        assert_eq!(context.running_exit, i + 1);
+        assert_eq!(context.fault_entry, i + 1);
+
+        resume_step(&mut machine, &mut context);
+        assert!(core::ptr::eq(machine.active(), &BOOST_STATE));
+        assert_eq!(context.fault_exit, i + 1);
+        assert_eq!(context.running_entry, i + 2);
+        assert_eq!(context.fast_entry, i + 2);
+        assert_eq!(context.boost_entry, i + 2);
+        assert_eq!(context.slow_entry, 1);
+        assert_eq!(context.cruise_entry, 1);
+        assert_eq!(context.idle_entry, 1);
+    }
+}
@@ -0,0 +1,218 @@
+struct Context {
+    entries: usize,
+    exits: usize,
+    actions: usize,
+}
+
+enum Event {
+    Stay,
+    Down,
+    Deep,
+    Orphan,
+    Stray,
+    Ignored,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+struct LevelState(usize);
+struct OrphanState;
+struct StrayState;
+
+impl LevelState {
+    fn action(context: &mut Context, _event: &Event) {
+        context.actions += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for LevelState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        match self.0 {
+            0 => None,
+            level => Some(&LEVEL_STATES[level - 1]),
+        }
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.entries += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        if self.0 != 1 {
+            return Transition::Unknown;
+        }
+
+        match event {
+            Event::Stay => Transition::Internal(Some(Self::action)),
+            Event::Down => Transition::Local(&LEVEL_STATES[2], Some(Self::action)),
+            Event::Deep => Transition::Local(&LEVEL_STATES[9], Some(Self::action)),
+            Event::Orphan => Transition::Local(&ORPHAN_STATE, Some(Self::action)),
+            Event::Stray => Transition::Local(&STRAY_STATE, Some(Self::action)),
+            Event::Ignored => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.exits += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for OrphanState {}
+
+impl hsm::State<Context, Event> for StrayState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&LEVEL_STATES[1])
+    }
+
+    fn initial(&self) -> hsm::Initial<Context, Event> {
+        Some((&LEVEL_STATES[3], None))
+    }
+}
+
+static LEVEL_STATES: [LevelState; 10] = [
+    LevelState(0),
+    LevelState(1),
+    LevelState(2),
+    LevelState(3),
+    LevelState(4),
+    LevelState(5),
+    LevelState(6),
+    LevelState(7),
+    LevelState(8),
+    LevelState(9),
+];
+static ORPHAN_STATE: OrphanState = OrphanState;
+static STRAY_STATE: StrayState = StrayState;
+
+fn create_context() -> Context {
+    Context {
+        entries: 0,
+        exits: 0,
+        actions: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&LEVEL_STATES[1])
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Stay),
+        Err(hsm::DispatchError::NotStarted)
+    );
+    assert_eq!(context.actions, 0);
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &LEVEL_STATES[1]));
+    assert_eq!(context.entries, 2);
+}
+
+#[test]
+fn outcomes() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &LEVEL_STATES[1]));
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Stay),
+        Ok(hsm::DispatchOutcome::Handled)
+    );
+    assert!(core::ptr::eq(machine.active(), &LEVEL_STATES[1]));
+    assert_eq!(context.actions, 1);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Down),
+        Ok(hsm::DispatchOutcome::Transitioned)
+    );
+    assert!(core::ptr::eq(machine.active(), &LEVEL_STATES[2]));
+    assert_eq!(context.actions, 2);
+    assert_eq!(context.entries, 3);
+}
+
+#[test]
+fn errors() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &LEVEL_STATES[1]));
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Ignored),
+        Err(hsm::DispatchError::UnhandledEvent)
+    );
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Deep),
+        Err(hsm::DispatchError::DepthExceeded)
+    );
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Orphan),
+        Err(hsm::DispatchError::DisjointStates)
+    );
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Stray),
+        Err(hsm::DispatchError::InvalidInitialState)
+    );
+
+    assert!(core::ptr::eq(machine.active(), &LEVEL_STATES[1]));
+    assert_eq!(context.entries, 2);
+    assert_eq!(context.exits, 0);
+    assert_eq!(context.actions, 0);
+}
+
+#[test]
+#[should_panic(expected = "State tree depth limit exceeded!")]
+fn depth_exceeded() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &LEVEL_STATES[1]));
+
+    machine.dispatch(&mut context, &Event::Deep);
+}
+
+#[test]
+fn deep_machine() {
+    let mut context = create_context();
+    let mut machine: hsm::StateMachine<Context, Event, 16> =
+        hsm::StateMachine::new(&LEVEL_STATES[1]);
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &LEVEL_STATES[1]));
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Deep),
+        Ok(hsm::DispatchOutcome::Transitioned)
+    );
+    assert!(core::ptr::eq(machine.active(), &LEVEL_STATES[9]));
+    assert_eq!(context.entries, 10);
+    assert_eq!(context.actions, 1);
+}
+
+#[test]
+fn shallow_machine() {
+    let mut context = create_context();
+    let mut machine: hsm::StateMachine<Context, Event, 1> =
+        hsm::StateMachine::new(&LEVEL_STATES[1]);
+
+    assert_eq!(
+        machine.try_start(&mut context),
+        Err(hsm::DispatchError::DepthExceeded)
+    );
+    assert!(!machine.is_started());
+    assert_eq!(context.entries, 0);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Stay),
+        Err(hsm::DispatchError::NotStarted)
+    );
+}
@@ -0,0 +1,351 @@
+struct Context {
+    reading: u32,
+    idle_entry: usize,
+    low_entry: usize,
+    high_entry: usize,
+    idle_exit: usize,
+    branch_action: usize,
+}
+
+enum Event {
+    Measure(u32),
+    Select(u32),
+    Strict(u32),
+    Probe(u32),
+    Check(u32),
+    Reset,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+type Branch = hsm::Branch<Context, Event>;
+
+struct RootState;
+struct IdleState;
+struct LowState;
+struct HighState;
+struct BrokenState;
+
+impl RootState {
+    fn high_reading(context: &Context, _event: &Event) -> bool {
+        context.reading > 100
+    }
+
+    fn low_reading(context: &Context, _event: &Event) -> bool {
+        context.reading < 10
+    }
+
+    fn idle_value(_context: &Context, event: &Event) -> bool {
+        matches!(event, Event::Select(0))
+    }
+
+    fn low_value(_context: &Context, event: &Event) -> bool {
+        matches!(event, Event::Select(value) | Event::Strict(value) if *value < 10)
+    }
+
+    fn high_value(_context: &Context, event: &Event) -> bool {
+        matches!(event, Event::Strict(value) if *value > 100)
+    }
+
+    fn action(context: &mut Context, _event: &Event) {
+        context.branch_action += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&IDLE_STATE, None))
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Reset => Transition::Local(&IDLE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl IdleState {
+    fn record(context: &mut Context, event: &Event) {
+        if let Event::Measure(value) | Event::Probe(value) | Event::Check(value) = event {
+            context.reading = *value;
+        }
+    }
+}
+
+impl hsm::State<Context, Event> for IdleState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.idle_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Measure(_) => Transition::Choice(&CLASSIFY_CHOICE, Some(Self::record)),
+            Event::Select(_) => Transition::Junction(&SELECT_JUNCTION, None),
+            Event::Strict(_) => Transition::Junction(&STRICT_JUNCTION, None),
+            Event::Probe(_) => Transition::Choice(&PROBE_CHOICE, Some(Self::record)),
+            Event::Check(_) => Transition::Choice(&CHECK_CHOICE, Some(Self::record)),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.idle_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for LowState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.low_entry += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for HighState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.high_entry += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for BrokenState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&LOW_STATE, None))
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static IDLE_STATE: IdleState = IdleState;
+static LOW_STATE: LowState = LowState;
+static HIGH_STATE: HighState = HighState;
+static BROKEN_STATE: BrokenState = BrokenState;
+
+static CLASSIFY_CHOICE: hsm::Choice<Context, Event> = hsm::Choice::new(
+    &ROOT_STATE,
+    &[
+        Branch::new(
+            RootState::high_reading,
+            &HIGH_STATE,
+            Some(RootState::action),
+        ),
+        Branch::otherwise(&LOW_STATE, None),
+    ],
+);
+
+static PROBE_CHOICE: hsm::Choice<Context, Event> = hsm::Choice::new(
+    &ROOT_STATE,
+    &[Branch::new(RootState::low_reading, &LOW_STATE, None)],
+);
+
+static CHECK_CHOICE: hsm::Choice<Context, Event> = hsm::Choice::new(
+    &ROOT_STATE,
+    &[
+        Branch::new(RootState::high_reading, &BROKEN_STATE, None),
+        Branch::otherwise(&LOW_STATE, None),
+    ],
+);
+
+static SELECT_JUNCTION: hsm::Junction<Context, Event> = hsm::Junction::new(&[
+    Branch::new(RootState::idle_value, &IDLE_STATE, None),
+    Branch::new(RootState::low_value, &LOW_STATE, None),
+    Branch::otherwise(&HIGH_STATE, Some(RootState::action)),
+]);
+
+static STRICT_JUNCTION: hsm::Junction<Context, Event> = hsm::Junction::new(&[
+    Branch::new(RootState::low_value, &LOW_STATE, None),
+    Branch::new(RootState::high_value, &HIGH_STATE, None),
+]);
+
+fn create_context() -> Context {
+    Context {
+        reading: 0,
+        idle_entry: 0,
+        low_entry: 0,
+        high_entry: 0,
+        idle_exit: 0,
+        branch_action: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+}
+
+fn reset_step(machine: &mut StateMachine, context: &mut Context) {
+    let reset_event = Event::Reset;
+    machine.dispatch(context, &reset_event);
+}
+
+#[test]
+fn multi_choice() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Measure(500));
+        assert!(core::ptr::eq(machine.active(), &HIGH_STATE));
+        assert_eq!(context.reading, 500);
+        assert_eq!(context.idle_exit, 2 * i + 1);
+        assert_eq!(context.high_entry, i + 1);
+        assert_eq!(context.branch_action, i + 1);
+
+        reset_step(&mut machine, &mut context);
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+
+        machine.dispatch(&mut context, &Event::Measure(50));
+        assert!(core::ptr::eq(machine.active(), &LOW_STATE));
+        assert_eq!(context.reading, 50);
+        assert_eq!(context.idle_exit, 2 * i + 2);
+        assert_eq!(context.low_entry, i + 1);
+        assert_eq!(context.branch_action, i + 1);
+
+        reset_step(&mut machine, &mut context);
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    }
+}
+
+#[test]
+fn multi_junction() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Select(0));
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+        assert_eq!(context.idle_exit, 2 * i);
+        assert_eq!(context.idle_entry, 2 * i + 1);
+
+        machine.dispatch(&mut context, &Event::Select(5));
+        assert!(core::ptr::eq(machine.active(), &LOW_STATE));
+        assert_eq!(context.low_entry, i + 1);
+
+        reset_step(&mut machine, &mut context);
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+
+        machine.dispatch(&mut context, &Event::Select(50));
+        assert!(core::ptr::eq(machine.active(), &HIGH_STATE));
+        assert_eq!(context.high_entry, i + 1);
+        assert_eq!(context.branch_action, i + 1);
+
+        reset_step(&mut machine, &mut context);
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    }
+}
+
+#[test]
+fn disabled_junction() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Strict(50)),
+        Err(hsm::DispatchError::NoEnabledBranch)
+    );
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(context.idle_exit, 0);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Strict(500)),
+        Ok(hsm::DispatchOutcome::Transitioned)
+    );
+    assert!(core::ptr::eq(machine.active(), &HIGH_STATE));
+    assert_eq!(context.idle_exit, 1);
+}
+
+#[test]
+fn disabled_choice() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Probe(5)),
+        Ok(hsm::DispatchOutcome::Transitioned)
+    );
+    assert!(core::ptr::eq(machine.active(), &LOW_STATE));
+    assert_eq!(context.low_entry, 1);
+
+    reset_step(&mut machine, &mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(context.idle_exit, 1);
+    assert_eq!(context.idle_entry, 2);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Probe(50)),
+        Ok(hsm::DispatchOutcome::Transitioned)
+    );
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(context.reading, 50);
+    assert_eq!(context.idle_exit, 2);
+    assert_eq!(context.idle_entry, 3);
+    assert_eq!(context.low_entry, 1);
+
+    assert_eq!(
+        machine.try_dispatch(&mut context, &Event::Probe(5)),
+        Err(hsm::DispatchError::NoEnabledBranch)
+    );
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+    assert_eq!(context.reading, 50);
+    assert_eq!(context.idle_exit, 2);
+    assert_eq!(context.idle_entry, 3);
+}
+
+#[test]
+fn broken_choice() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+
+    for value in [5, 500] {
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Check(value)),
+            Err(hsm::DispatchError::InvalidInitialState)
+        );
+        assert!(core::ptr::eq(machine.active(), &IDLE_STATE));
+        assert_eq!(context.reading, 0);
+        assert_eq!(context.idle_exit, 0);
+        assert_eq!(context.idle_entry, 1);
+        assert_eq!(context.low_entry, 0);
+    }
+}
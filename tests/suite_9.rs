@@ -0,0 +1,392 @@
+struct Context {
+    connected_entry: usize,
+    link_entry: usize,
+    degraded_entry: usize,
+    idle_entry: usize,
+    open_entry: usize,
+    connected_exit: usize,
+    link_exit: usize,
+    degraded_exit: usize,
+    idle_exit: usize,
+    open_exit: usize,
+    link_ping: usize,
+    session_ping: usize,
+    connected_ping: usize,
+    connected_status: usize,
+}
+
+enum Event {
+    Connect,
+    Resume,
+    Disconnect,
+    Degrade,
+    Recover,
+    Open,
+    Close,
+    Ping,
+    Status,
+    Fault,
+}
+
+type Transition = hsm::Transition<Context, Event>;
+
+type Initial = hsm::Initial<Context, Event>;
+
+type StateMachine = hsm::StateMachine<Context, Event>;
+
+type RegionRef = &'static (dyn hsm::State<Context, Event> + Sync);
+
+struct RootState;
+struct DisconnectedState;
+struct ConnectedState;
+struct LinkRegion;
+struct LinkUpState;
+struct LinkDegradedState;
+struct SessionRegion;
+struct SessionIdleState;
+struct SessionOpenState;
+
+impl hsm::State<Context, Event> for RootState {
+    fn initial(&self) -> Initial {
+        Some((&DISCONNECTED_STATE, None))
+    }
+}
+
+impl hsm::State<Context, Event> for DisconnectedState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Connect => Transition::Local(&CONNECTED_STATE, None),
+            Event::Resume => Transition::Local(&SESSION_OPEN_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+}
+
+impl ConnectedState {
+    fn ping(context: &mut Context, _event: &Event) {
+        context.connected_ping += 1;
+    }
+
+    fn status(context: &mut Context, _event: &Event) {
+        context.connected_status += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for ConnectedState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&ROOT_STATE)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.connected_entry += 1;
+    }
+
+    fn regions(&self) -> &'static [RegionRef] {
+        &CONNECTED_REGIONS
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Disconnect => Transition::Local(&DISCONNECTED_STATE, None),
+            Event::Ping => Transition::Internal(Some(Self::ping)),
+            Event::Status => Transition::Internal(Some(Self::status)),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.connected_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for LinkRegion {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&CONNECTED_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&LINK_UP_STATE, None))
+    }
+}
+
+impl LinkUpState {
+    fn ping(context: &mut Context, _event: &Event) {
+        context.link_ping += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for LinkUpState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&LINK_REGION)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.link_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Degrade | Event::Fault => Transition::Local(&LINK_DEGRADED_STATE, None),
+            Event::Ping => Transition::Internal(Some(Self::ping)),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.link_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for LinkDegradedState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&LINK_REGION)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.degraded_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Recover => Transition::Local(&LINK_UP_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.degraded_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for SessionRegion {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&CONNECTED_STATE)
+    }
+
+    fn initial(&self) -> Initial {
+        Some((&SESSION_IDLE_STATE, None))
+    }
+}
+
+impl SessionIdleState {
+    fn ping(context: &mut Context, _event: &Event) {
+        context.session_ping += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for SessionIdleState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&SESSION_REGION)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.idle_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Open => Transition::Local(&SESSION_OPEN_STATE, None),
+            Event::Ping => Transition::Internal(Some(Self::ping)),
+            Event::Fault => Transition::Error(hsm::DispatchError::InvalidState),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.idle_exit += 1;
+    }
+}
+
+impl hsm::State<Context, Event> for SessionOpenState {
+    fn parent(&self) -> Option<&'static dyn hsm::State<Context, Event>> {
+        Some(&SESSION_REGION)
+    }
+
+    fn entry(&self, context: &mut Context) {
+        context.open_entry += 1;
+    }
+
+    fn transition(&self, _context: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::Close => Transition::Local(&SESSION_IDLE_STATE, None),
+            _ => Transition::Unknown,
+        }
+    }
+
+    fn exit(&self, context: &mut Context) {
+        context.open_exit += 1;
+    }
+}
+
+static ROOT_STATE: RootState = RootState;
+static DISCONNECTED_STATE: DisconnectedState = DisconnectedState;
+static CONNECTED_STATE: ConnectedState = ConnectedState;
+static LINK_REGION: LinkRegion = LinkRegion;
+static LINK_UP_STATE: LinkUpState = LinkUpState;
+static LINK_DEGRADED_STATE: LinkDegradedState = LinkDegradedState;
+static SESSION_REGION: SessionRegion = SessionRegion;
+static SESSION_IDLE_STATE: SessionIdleState = SessionIdleState;
+static SESSION_OPEN_STATE: SessionOpenState = SessionOpenState;
+
+static CONNECTED_REGIONS: [RegionRef; 2] = [&LINK_REGION, &SESSION_REGION];
+
+fn create_context() -> Context {
+    Context {
+        connected_entry: 0,
+        link_entry: 0,
+        degraded_entry: 0,
+        idle_entry: 0,
+        open_entry: 0,
+        connected_exit: 0,
+        link_exit: 0,
+        degraded_exit: 0,
+        idle_exit: 0,
+        open_exit: 0,
+        link_ping: 0,
+        session_ping: 0,
+        connected_ping: 0,
+        connected_status: 0,
+    }
+}
+
+fn create_machine() -> StateMachine {
+    StateMachine::new(&ROOT_STATE)
+}
+
+fn assert_configuration(
+    machine: &StateMachine,
+    states: &[&'static dyn hsm::State<Context, Event>],
+) {
+    assert_eq!(machine.configuration().count(), states.len());
+
+    for (active_state, state) in machine.configuration().zip(states) {
+        assert!(core::ptr::eq(active_state, *state));
+    }
+}
+
+#[test]
+fn startup() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+    assert!(core::ptr::eq(machine.active(), &ROOT_STATE));
+
+    machine.start(&mut context);
+    assert_configuration(&machine, &[&DISCONNECTED_STATE]);
+
+    machine.dispatch(&mut context, &Event::Connect);
+    assert_configuration(&machine, &[&LINK_UP_STATE, &SESSION_IDLE_STATE]);
+    assert_eq!(context.connected_entry, 1);
+    assert_eq!(context.link_entry, 1);
+    assert_eq!(context.idle_entry, 1);
+}
+
+#[test]
+fn broadcast() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Connect);
+    assert_configuration(&machine, &[&LINK_UP_STATE, &SESSION_IDLE_STATE]);
+
+    for i in 0..1000 {
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Ping),
+            Ok(hsm::DispatchOutcome::Handled)
+        );
+        assert_eq!(context.link_ping, i + 1);
+        assert_eq!(context.session_ping, i + 1);
+        assert_eq!(context.connected_ping, 0);
+
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Status),
+            Ok(hsm::DispatchOutcome::Handled)
+        );
+        assert_eq!(context.connected_status, i + 1);
+    }
+}
+
+#[test]
+fn multi_region() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    for i in 0..1000 {
+        machine.dispatch(&mut context, &Event::Connect);
+        assert_configuration(&machine, &[&LINK_UP_STATE, &SESSION_IDLE_STATE]);
+        assert_eq!(context.connected_entry, i + 1);
+
+        machine.dispatch(&mut context, &Event::Open);
+        assert_configuration(&machine, &[&LINK_UP_STATE, &SESSION_OPEN_STATE]);
+        assert_eq!(context.idle_exit, 2 * i + 1);
+        assert_eq!(context.open_entry, i + 1);
+
+        machine.dispatch(&mut context, &Event::Degrade);
+        assert_configuration(&machine, &[&LINK_DEGRADED_STATE, &SESSION_OPEN_STATE]);
+        assert_eq!(context.link_exit, 2 * i + 1);
+        assert_eq!(context.degraded_entry, i + 1);
+
+        machine.dispatch(&mut context, &Event::Close);
+        assert_configuration(&machine, &[&LINK_DEGRADED_STATE, &SESSION_IDLE_STATE]);
+        assert_eq!(context.open_exit, i + 1);
+
+        machine.dispatch(&mut context, &Event::Recover);
+        assert_configuration(&machine, &[&LINK_UP_STATE, &SESSION_IDLE_STATE]);
+        assert_eq!(context.degraded_exit, i + 1);
+
+        machine.dispatch(&mut context, &Event::Disconnect);
+        assert_configuration(&machine, &[&DISCONNECTED_STATE]);
+        assert_eq!(context.link_exit, 2 * i + 2);
+        assert_eq!(context.idle_exit, 2 * i + 2);
+        assert_eq!(context.connected_exit, i + 1);
+    }
+}
+
+#[test]
+fn region_entry() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+
+    machine.dispatch(&mut context, &Event::Resume);
+    assert_configuration(&machine, &[&LINK_UP_STATE, &SESSION_OPEN_STATE]);
+    assert_eq!(context.connected_entry, 1);
+    assert_eq!(context.link_entry, 1);
+    assert_eq!(context.idle_entry, 0);
+    assert_eq!(context.open_entry, 1);
+
+    machine.stop(&mut context);
+    assert_eq!(machine.configuration().count(), 0);
+    assert_eq!(context.link_exit, 1);
+    assert_eq!(context.open_exit, 1);
+    assert_eq!(context.connected_exit, 1);
+}
+
+#[test]
+fn partial_failure() {
+    let mut context = create_context();
+    let mut machine = create_machine();
+
+    machine.start(&mut context);
+    machine.dispatch(&mut context, &Event::Connect);
+
+    for i in 0..1000 {
+        assert_eq!(
+            machine.try_dispatch(&mut context, &Event::Fault),
+            Err(hsm::DispatchError::InvalidState)
+        );
+        assert_configuration(&machine, &[&LINK_DEGRADED_STATE, &SESSION_IDLE_STATE]);
+        assert_eq!(context.degraded_entry, i + 1);
+        assert_eq!(context.idle_exit, 0);
+
+        machine.dispatch(&mut context, &Event::Recover);
+        assert_configuration(&machine, &[&LINK_UP_STATE, &SESSION_IDLE_STATE]);
+    }
+}
@@ -0,0 +1,23 @@
+struct Context;
+
+enum Event {
+    Toggle,
+}
+
+hsm::hsm! {
+    context = Context;
+    event = Event;
+    depth = 2;
+
+    ROOT_STATE {
+        initial = ON_STATE;
+
+        ON_STATE {
+            initial = DIM_STATE;
+
+            DIM_STATE {}
+        }
+    }
+}
+
+fn main() {}
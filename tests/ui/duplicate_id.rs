@@ -0,0 +1,21 @@
+struct Context;
+
+enum Event {
+    Toggle,
+}
+
+hsm::hsm! {
+    context = Context;
+    event = Event;
+
+    ROOT_STATE {
+        id = 1;
+        initial = OFF_STATE;
+
+        OFF_STATE {
+            id = 1;
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+struct Context;
+
+enum Event {
+    Toggle,
+}
+
+hsm::hsm! {
+    context = Context;
+    event = Event;
+
+    ROOT_STATE {
+        initial = OFF_STATE;
+
+        OFF_STATE {}
+
+        ON_STATE {
+            OFF_STATE {}
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+struct Context;
+
+enum Event {
+    Toggle,
+}
+
+hsm::hsm! {
+    context = Context;
+    event = Event;
+
+    ROOT_STATE {
+        initial = OFF_STATE;
+
+        OFF_STATE {}
+    }
+
+    SPARE_STATE {}
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+struct Context;
+
+enum Event {
+    Toggle,
+}
+
+hsm::hsm! {
+    context = Context;
+    event = Event;
+
+    ROOT_STATE {
+        initial = DIM_STATE;
+
+        ON_STATE {
+            initial = DIM_STATE;
+
+            DIM_STATE {}
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+struct Context;
+
+enum Event {
+    Toggle,
+}
+
+type StateMachine = hsm::StateMachine<Context, Event, 2>;
+
+hsm::hsm! {
+    context = Context;
+    event = Event;
+    machine = StateMachine;
+
+    ROOT_STATE {
+        initial = ON_STATE;
+
+        ON_STATE {
+            initial = DIM_STATE;
+
+            DIM_STATE {}
+        }
+    }
+}
+
+fn main() {}
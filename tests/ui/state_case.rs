@@ -0,0 +1,18 @@
+struct Context;
+
+enum Event {
+    Toggle,
+}
+
+hsm::hsm! {
+    context = Context;
+    event = Event;
+
+    ROOT_STATE {
+        initial = OffState;
+
+        OffState {}
+    }
+}
+
+fn main() {}